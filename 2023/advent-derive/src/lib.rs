@@ -237,31 +237,66 @@ fn find_entries() -> Vec<Entry> {
     years
 }
 
+enum CacheIf {
+    Some,
+    Ok,
+    Predicate(syn::Expr),
+}
+
 struct MemoizeArgs {
     key_args: Option<Vec<Ident>>,
+    persist: bool,
+    cache_if: Option<CacheIf>,
 }
 
 impl syn::parse::Parse for MemoizeArgs {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        if input.is_empty() {
-            return Ok(Self { key_args: None });
-        }
+        let mut args = Self {
+            key_args: None,
+            persist: false,
+            cache_if: None,
+        };
+
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            if ident == "key" {
+                input.parse::<Token![=]>()?;
+
+                let content;
+                syn::parenthesized!(content in input);
+                let keys: Punctuated<Ident, Token![,]> =
+                    content.parse_terminated(Ident::parse, Token![,])?;
+                args.key_args = Some(keys.into_iter().collect());
+            } else if ident == "persist" {
+                args.persist = true;
+            } else if ident == "cache_if" {
+                input.parse::<Token![=]>()?;
+
+                let expr: syn::Expr = input.parse()?;
+                args.cache_if = Some(match &expr {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }) => match lit.value().as_str() {
+                        "some" => CacheIf::Some,
+                        "ok" => CacheIf::Ok,
+                        _ => CacheIf::Predicate(lit.parse()?),
+                    },
+                    _ => CacheIf::Predicate(expr),
+                });
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "expected `key`, `persist` or `cache_if`",
+                ));
+            }
 
-        let ident: Ident = input.parse()?;
-        if ident != "key" {
-            return Err(syn::Error::new(ident.span(), "expected `key`"));
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
         }
 
-        input.parse::<Token![=]>()?;
-
-        let content;
-        syn::parenthesized!(content in input);
-        let args: Punctuated<Ident, Token![,]> =
-            content.parse_terminated(Ident::parse, Token![,])?;
-
-        Ok(Self {
-            key_args: Some(args.into_iter().collect()),
-        })
+        Ok(args)
     }
 }
 
@@ -378,26 +413,103 @@ pub fn memoize(
         quote! { (#(#key_clone_exprs),*) }
     };
 
+    let record_loaded_hit = if args.persist {
+        quote! {
+            if #cache_mod_name::loaded().read().unwrap().contains_key(&__key) {
+                #cache_mod_name::STATS.record_loaded_hit();
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let persist_items = if args.persist {
+        quote! {
+            /// Keys loaded from disk outside of any `MemoScope`.
+            pub static LOADED: LazyLock<Arc<RwLock<HashMap<#key_type, ()>>>> = LazyLock::new(|| Arc::new(RwLock::new(HashMap::new())));
+
+            struct LoadedMarker;
+
+            /// Keys loaded from disk into `current()`, scoped the same way.
+            pub fn loaded() -> Arc<RwLock<HashMap<#key_type, ()>>> {
+                crate::utils::memoize::scoped_cache::<LoadedMarker, #key_type, ()>(None)
+                    .unwrap_or_else(|| LOADED.clone())
+            }
+
+            fn __load_cache() -> u64 {
+                let Some(entries) = crate::utils::memoize::load_cache_entries::<#key_type, #return_type>(#fn_name_str) else {
+                    return 0;
+                };
+                let cache = current();
+                let mut cache = cache.write().unwrap();
+                let loaded = loaded();
+                let mut loaded = loaded.write().unwrap();
+                let count = entries.len() as u64;
+                for (key, value) in entries {
+                    loaded.insert(key.clone(), ());
+                    cache.insert(key, value);
+                }
+                STATS.record_loaded(count);
+                count
+            }
+
+            fn __save_cache() {
+                let cache = current();
+                let cache = cache.read().unwrap();
+                if !cache.is_empty() {
+                    crate::utils::memoize::save_cache_entries(#fn_name_str, cache.iter());
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let clear_loaded = if args.persist {
+        quote! { loaded().write().unwrap().clear(); }
+    } else {
+        quote! {}
+    };
+
+    let (load_cache, save_cache) = if args.persist {
+        (quote! { Some(__load_cache) }, quote! { Some(__save_cache) })
+    } else {
+        (quote! { None }, quote! { None })
+    };
+
+    let should_cache = match &args.cache_if {
+        None => quote! { true },
+        Some(CacheIf::Some) => quote! { __result.is_some() },
+        Some(CacheIf::Ok) => quote! { __result.is_ok() },
+        Some(CacheIf::Predicate(predicate)) => quote! { (#predicate)(&__result) },
+    };
+
     let output = quote! {
         #[allow(unused)]
         #(#fn_attrs)*
         #fn_vis fn #fn_name #fn_generics(#fn_inputs) #fn_output {
             let __key = #key_expr;
+            let __cache = #cache_mod_name::current();
 
             {
-                let cache = #cache_mod_name::CACHE.read().unwrap();
+                let cache = __cache.read().unwrap();
                 if let Some(result) = cache.get(&__key) {
                     #cache_mod_name::STATS.record_hit();
+                    #record_loaded_hit
                     return result.clone();
                 }
             }
 
             #cache_mod_name::STATS.record_miss();
+            let __call = #cache_mod_name::STATS.enter_call(&#cache_mod_name::CALL_STATE);
             let __result = #unmemoized_fn_name(#(#arg_names),*);
+            drop(__call);
 
-            {
-                let mut cache = #cache_mod_name::CACHE.write().unwrap();
+            if #should_cache {
+                let mut cache = __cache.write().unwrap();
                 cache.insert(__key, __result.clone());
+            } else {
+                #cache_mod_name::STATS.record_skipped();
             }
 
             __result
@@ -410,29 +522,57 @@ pub fn memoize(
         #[doc(hidden)]
         #fn_vis mod #cache_mod_name {
             use super::*;
-            use std::sync::{RwLock, LazyLock};
+            use std::sync::{Arc, RwLock, LazyLock};
             use std::collections::HashMap;
 
-            pub static CACHE: LazyLock<RwLock<HashMap<#key_type, #return_type>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+            pub static CACHE: LazyLock<Arc<RwLock<HashMap<#key_type, #return_type>>>> = LazyLock::new(|| Arc::new(RwLock::new(HashMap::new())));
             pub static STATS: AtomicStats = AtomicStats::new();
 
+            thread_local! {
+                pub static CALL_STATE: std::cell::Cell<crate::utils::memoize::CallState> =
+                    const { std::cell::Cell::new(crate::utils::memoize::CallState::new()) };
+            }
+
+            struct ScopeMarker;
+
+            /// The cache of the active `MemoScope`, or the global one outside of any scope.
+            pub fn current() -> Arc<RwLock<HashMap<#key_type, #return_type>>> {
+                crate::utils::memoize::scoped_cache::<ScopeMarker, #key_type, #return_type>(Some(&STATS))
+                    .unwrap_or_else(|| CACHE.clone())
+            }
+
+            #persist_items
+
             pub fn clear() {
-                let mut cache = CACHE.write().unwrap();
+                let cache = current();
+                let mut cache = cache.write().unwrap();
                 cache.clear();
+                #clear_loaded
             }
 
             pub fn invalidate(key: &#key_type) {
-                let mut cache = CACHE.write().unwrap();
+                let cache = current();
+                let mut cache = cache.write().unwrap();
                 cache.remove(key);
             }
 
             pub fn stats() -> MemoizeStats {
-                let cache = CACHE.read().unwrap();
+                let scoped = crate::utils::memoize::scoped_cache::<ScopeMarker, #key_type, #return_type>(Some(&STATS));
+                let cache_size = match scoped {
+                    Some(cache) => cache.read().unwrap().len(),
+                    None => CACHE.read().unwrap().len().max(STATS.get_last_scope_cache_size()),
+                };
                 MemoizeStats {
                     function_name: #fn_name_str,
                     hits: STATS.get_hits(),
                     misses: STATS.get_misses(),
-                    cache_size: cache.len(),
+                    skipped: STATS.get_skipped(),
+                    cache_size,
+                    loaded: STATS.get_loaded(),
+                    loaded_hits: STATS.get_loaded_hits(),
+                    compute_time: STATS.get_compute_time(),
+                    approx_bytes: cache_size * std::mem::size_of::<(#key_type, #return_type)>(),
+                    max_depth: STATS.get_max_depth(),
                 }
             }
 
@@ -458,6 +598,8 @@ pub fn memoize(
                     get_stats: __get_stats,
                     clear_cache: __clear_cache,
                     reset_stats: __reset_stats,
+                    load_cache: #load_cache,
+                    save_cache: #save_cache,
                 }
             }
         }
@@ -467,3 +609,133 @@ pub fn memoize(
 
     output.into()
 }
+
+struct TileArgs {
+    chars: Vec<syn::LitChar>,
+    markers: Vec<syn::LitChar>,
+    display: Option<syn::LitChar>,
+}
+
+impl syn::parse::Parse for TileArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self {
+            chars: Vec::new(),
+            markers: Vec::new(),
+            display: None,
+        };
+
+        while !input.is_empty() {
+            if input.peek(syn::LitChar) {
+                args.chars.push(input.parse()?);
+            } else {
+                let ident: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                if ident == "marker" {
+                    args.markers.push(input.parse()?);
+                } else if ident == "display" {
+                    args.display = Some(input.parse()?);
+                } else {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "expected a character, `marker` or `display`",
+                    ));
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+#[proc_macro_derive(GridTile, attributes(tile))]
+pub fn grid_tile(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match grid_tile_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn grid_tile_impl(input: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let syn::Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "GridTile can only be derived for enums",
+        ));
+    };
+
+    let mut parse_arms = Vec::new();
+    let mut display_arms = Vec::new();
+    let mut all_markers = Vec::new();
+
+    for variant in &data.variants {
+        let variant_name = &variant.ident;
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "GridTile variants cannot have fields",
+            ));
+        }
+
+        let attr = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("tile"))
+            .ok_or_else(|| syn::Error::new_spanned(variant, "missing #[tile('c')] attribute"))?;
+        let args: TileArgs = attr.parse_args()?;
+
+        let display = match (&args.display, args.chars.first()) {
+            (Some(display), _) | (None, Some(display)) => display,
+            (None, None) => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "expected at least one character",
+                ))
+            }
+        };
+
+        let parsed = args.chars.iter().chain(&args.markers);
+        parse_arms.push(quote! { #(#parsed)|* => Some(#name::#variant_name), });
+        display_arms.push(quote! { #name::#variant_name => #display, });
+        all_markers.extend(args.markers);
+    }
+
+    Ok(quote! {
+        impl crate::utils::tile::GridTile for #name {
+            const MARKERS: &'static [char] = &[#(#all_markers),*];
+
+            fn from_char(c: char) -> Option<Self> {
+                match c {
+                    #(#parse_arms)*
+                    _ => None,
+                }
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                    #(#display_arms)*
+                }
+            }
+        }
+
+        impl TryFrom<char> for #name {
+            type Error = crate::utils::tile::ParseTileError;
+
+            fn try_from(c: char) -> Result<Self, Self::Error> {
+                <Self as crate::utils::tile::GridTile>::from_char(c)
+                    .ok_or(crate::utils::tile::ParseTileError::InvalidChar(c))
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", <Self as crate::utils::tile::GridTile>::to_char(self))
+            }
+        }
+    })
+}
@@ -1,22 +1,35 @@
 use array2d::Array2D;
-use colored::Colorize;
 use std::fmt::Display;
+use strum::IntoEnumIterator;
 
-use crate::utils::{direction::Direction, map::InteractWithPoint, point::Point};
+use crate::utils::{
+    direction::Direction,
+    map::InteractWithPoint,
+    point::Point,
+    polygon::Polygon,
+    tile::GridTile,
+};
 
 pub fn part1(input: String) -> String {
-    let mut grid = part1::parse(&input);
+    let (shapes, start_point) = parse(&input);
+    let mut grid = part1::into_pipes(&shapes);
     // part1::print_grid(&grid);
-    let distance = part1::walk_grid(&mut grid);
+    let distance = part1::walk_grid(&mut grid, &start_point);
     distance.to_string()
 }
 
+/// The pipe map and the position of `S`.
+fn parse(input: &str) -> (Array2D<PipeShape>, Point) {
+    let parsed = PipeShape::parse_map::<Array2D<_>>(input).expect("Failed to parse map");
+    let start_point = parsed.marker('S').expect("No start found");
+    (parsed.map, start_point)
+}
+
 mod part1 {
 
     use super::*;
-    pub(crate) fn walk_grid(grid: &mut Array2D<Pipe>) -> usize {
-        let start_point = find_start(&grid);
-        let (mut point1, mut point2) = find_connected_to_start(&grid, &start_point);
+    pub(crate) fn walk_grid(grid: &mut Array2D<Pipe>, start_point: &Point) -> usize {
+        let (mut point1, mut point2) = find_connected_to_start(grid, start_point);
         let mut distance = 0;
         loop {
             // print_grid(grid);
@@ -102,29 +115,12 @@ mod part1 {
         (connected[0], connected[1])
     }
 
-    fn find_start(grid: &Array2D<Pipe>) -> Point {
-        for (y, row) in grid.rows_iter().enumerate() {
-            for (x, pipe) in row.enumerate() {
-                if pipe.shape == PipeShape::Start {
-                    return Point::new(x as i32, y as i32);
-                }
-            }
-        }
-        panic!("No start found");
-    }
-
-    pub(crate) fn parse(input: &str) -> Array2D<Pipe> {
-        let vec: Vec<Vec<Pipe>> = input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| PipeShape::from(c))
-                    .map(|pipe| Pipe::new(pipe, 0))
-                    .collect()
-            })
-            .collect();
-
-        Array2D::from_rows(&vec).unwrap()
+    pub(crate) fn into_pipes(shapes: &Array2D<PipeShape>) -> Array2D<Pipe> {
+        let pipes = shapes
+            .elements_row_major_iter()
+            .map(|&shape| Pipe::new(shape, 0))
+            .collect::<Vec<_>>();
+        Array2D::from_row_major(&pipes, shapes.num_rows(), shapes.num_columns()).unwrap()
     }
 
     #[derive(Debug, Clone)]
@@ -154,342 +150,62 @@ mod part1 {
 }
 
 pub fn part2(input: String) -> String {
-    let mut grid = part2::parse(&input);
-    part2::walk_grid(&mut grid);
-    // part2::print_grid(&grid);
-    let mut grid = part2::expand_grid(&grid);
-    part2::flood_grid(&mut grid);
+    let (grid, start_point) = parse(&input);
+    let pipe_loop = part2::trace_loop(&grid, start_point);
 
-    // part2::print_grid(&grid);
-
-    grid.rows_iter()
-        .map(|row| {
-            row.filter(|pipe| pipe.state == part2::PipeState::Unknown && !pipe.fake)
-                .count()
-        })
-        .sum::<usize>()
-        .to_string()
+    Polygon::new(pipe_loop).interior_points().to_string()
 }
 
 mod part2 {
     use super::*;
 
-    pub fn flood_grid(grid: &mut Array2D<Pipe>) {
-        let mut left_points = vec![Point::new(0, 0)];
-
-        while !left_points.is_empty() {
-            let point = left_points.pop().unwrap();
-            let pipe = grid.get_point(&point).unwrap();
-            match pipe.state {
-                PipeState::Unknown => {
-                    grid.get_point_mut(&point).unwrap().state = PipeState::OutsideLoop;
-                }
-                _ => continue,
-            }
-
-            for neighbour in point.neighbours() {
-                let Some(pipe) = grid.get_point(&neighbour) else {
-                    continue;
-                };
-                if pipe.state != PipeState::Unknown {
-                    continue;
-                }
-                left_points.push(neighbour);
-            }
-        }
-    }
-
-    pub fn expand_grid(grid: &Array2D<Pipe>) -> Array2D<Pipe> {
-        let mut new_grid = Array2D::filled_with(
-            Pipe {
-                shape: PipeShape::Empty,
-                state: PipeState::Unknown,
-                fake: true,
-            },
-            grid.num_rows() * 3,
-            grid.num_columns() * 3,
-        );
-
-        for (y, row) in grid.rows_iter().enumerate() {
-            for (x, pipe) in row.enumerate() {
-                let new_x = x * 3;
-                let new_y = y * 3;
-                new_grid[(new_y + 1, new_x + 1)] = pipe.clone();
-
-                let mut pipe = pipe.clone();
-
-                if pipe.shape == PipeShape::Start {
-                    pipe.shape = find_start_shape(&grid, &Point::new(x as i32, y as i32));
-                }
-
-                for (y_offset, row) in pipe.enhance().rows_iter().enumerate() {
-                    for (x_offset, pipe) in row.enumerate() {
-                        new_grid[(new_y + y_offset, new_x + x_offset)] = pipe.clone();
-                    }
-                }
-            }
-        }
-
-        new_grid
-    }
-
-    pub(crate) fn walk_grid(grid: &mut Array2D<Pipe>) {
-        let start_point = find_start(&grid);
-
-        grid.get_point_mut(&start_point).unwrap().state = PipeState::PartOfLoop;
-
-        let (mut point1, mut point2) = find_connected_to_start(&grid, &start_point);
-        loop {
-            update_pipe(grid, point1);
-            update_pipe(grid, point2);
-
-            let Some(next_point1) = find_next_point(&grid, &point1) else {
-                break;
-            };
-
-            let Some(next_point2) = find_next_point(&grid, &point2) else {
-                break;
-            };
-
-            point1 = next_point1;
-            point2 = next_point2;
-        }
-    }
-
-    fn update_pipe(grid: &mut Array2D<Pipe>, point: Point) {
-        let pipe = grid.get_point_mut(&point).unwrap();
-        pipe.state = PipeState::PartOfLoop;
-    }
+    /// The tiles of the loop in walking order, starting at `S`.
+    pub(crate) fn trace_loop(grid: &Array2D<PipeShape>, start_point: Point) -> Vec<Point> {
+        let mut direction = Direction::iter()
+            .find(|&direction| {
+                grid.get_point(&(start_point + direction))
+                    .is_some_and(|pipe| pipe.is_connected(direction.opposite()))
+            })
+            .expect("Start should connect to the loop");
 
-    fn find_next_point(grid: &Array2D<Pipe>, point: &Point) -> Option<Point> {
-        let mut connected = Vec::new();
-        let pipe = grid.get_point(&point).unwrap();
-        for direction in pipe.shape.directions() {
-            let Some(point) = point.move_in_direction(direction).ok_map(grid) else {
-                continue;
-            };
-            if grid
+        let mut pipe_loop = vec![start_point];
+        let mut point = start_point + direction;
+        while point != start_point {
+            pipe_loop.push(point);
+            direction = grid
                 .get_point(&point)
                 .unwrap()
-                .shape
-                .is_connected(direction.opposite())
-            {
-                connected.push(point);
-            }
-        }
-        if connected.len() != 2 {
-            panic!("Point has {} connections", connected.len());
-        }
-        let first = grid.get_point(&connected[0]).unwrap();
-        if first.state == PipeState::Unknown {
-            return Some(connected[0]);
-        }
-        let second = grid.get_point(&connected[1]).unwrap();
-        if second.state == PipeState::Unknown {
-            return Some(connected[1]);
-        }
-        None
-    }
-
-    fn find_start_shape(grid: &Array2D<Pipe>, start_point: &Point) -> PipeShape {
-        let directions = find_directions_start(&grid, &start_point);
-        let (direction1, direction2) = (directions[0], directions[1]);
-        PipeShape::from_directions(&[direction1, direction2])
-    }
-
-    fn find_directions_start(grid: &Array2D<Pipe>, start_point: &Point) -> Vec<Direction> {
-        let mut directions = Vec::new();
-        for direction in &[
-            Direction::North,
-            Direction::South,
-            Direction::East,
-            Direction::West,
-        ] {
-            let Some(point) = start_point.move_in_direction(*direction).ok_map(grid) else {
-                continue;
-            };
-            let pipe = grid.get_point(&point).unwrap();
-            if pipe.shape.is_connected(direction.opposite()) {
-                directions.push(*direction);
-            }
-        }
-
-        if directions.len() != 2 {
-            panic!(
-                "Start point has {} connections ({:?})",
-                directions.len(),
-                directions
-            );
-        }
-        directions
-    }
-
-    fn find_connected_to_start(grid: &Array2D<Pipe>, start_point: &Point) -> (Point, Point) {
-        let directions = find_directions_start(&grid, &start_point);
-        let mut connected = Vec::new();
-        for direction in directions {
-            let Some(point) = start_point.move_in_direction(direction).ok_map(grid) else {
-                continue;
-            };
-            let pipe = grid.get_point(&point).unwrap();
-            if pipe.shape.is_connected(direction.opposite()) {
-                connected.push(point);
-            }
-        }
-
-        if connected.len() != 2 {
-            panic!(
-                "Start point has {} connections ({:?})",
-                connected.len(),
-                connected
-            );
-        }
-
-        (connected[0], connected[1])
-    }
-
-    fn find_start(grid: &Array2D<Pipe>) -> Point {
-        for (y, row) in grid.rows_iter().enumerate() {
-            for (x, pipe) in row.enumerate() {
-                if pipe.shape == PipeShape::Start {
-                    return Point::new(x as i32, y as i32);
-                }
-            }
-        }
-        panic!("No start found");
-    }
-
-    pub(crate) fn parse(input: &str) -> Array2D<Pipe> {
-        let vec: Vec<Vec<Pipe>> = input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| PipeShape::from(c))
-                    .map(|pipe| Pipe::new(pipe))
-                    .collect()
-            })
-            .collect();
-
-        Array2D::from_rows(&vec).unwrap()
-    }
-
-    #[derive(Debug, Clone)]
-    pub struct Pipe {
-        pub shape: PipeShape,
-        pub state: PipeState,
-        pub fake: bool,
-    }
-
-    impl Pipe {
-        fn new(pipe: PipeShape) -> Self {
-            Pipe {
-                shape: pipe,
-                state: PipeState::Unknown,
-                fake: false,
-            }
-        }
-    }
-
-    impl Display for Pipe {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            if self.fake {
-                return write!(f, "{}", format!("{}", self.shape).dimmed());
-            }
-            match self.state {
-                PipeState::PartOfLoop => write!(f, "{}", format!("{}", self.shape).red()),
-                PipeState::OutsideLoop => write!(f, "{}", format!("{}", self.shape).green()),
-                PipeState::Unknown => write!(f, "{}", format!("{}", self.shape).yellow()),
-            }
-        }
-    }
-
-    impl Pipe {
-        fn enhance(&self) -> Array2D<Pipe> {
-            let mut grid = Array2D::filled_with(
-                Pipe {
-                    shape: PipeShape::Empty,
-                    state: PipeState::Unknown,
-                    fake: true,
-                },
-                3,
-                3,
-            );
-
-            fn set(grid: &mut Array2D<Pipe>, current: &Pipe, x: usize, y: usize, pipe: PipeShape) {
-                grid[(y, x)] = Pipe {
-                    shape: pipe,
-                    state: current.state,
-                    fake: true,
-                };
-            }
-            grid[(1, 1)] = self.clone();
-
-            match self.shape {
-                PipeShape::Empty => {}
-                PipeShape::Start => {}
-                PipeShape::Vertical => {
-                    set(&mut grid, self, 1, 0, PipeShape::Vertical);
-                    set(&mut grid, self, 1, 2, PipeShape::Vertical);
-                }
-                PipeShape::Horizontal => {
-                    set(&mut grid, self, 0, 1, PipeShape::Horizontal);
-                    set(&mut grid, self, 2, 1, PipeShape::Horizontal);
-                }
-                PipeShape::NorthEast => {
-                    set(&mut grid, self, 1, 0, PipeShape::Vertical);
-                    set(&mut grid, self, 2, 1, PipeShape::Horizontal);
-                }
-                PipeShape::NorthWest => {
-                    set(&mut grid, self, 1, 0, PipeShape::Vertical);
-                    set(&mut grid, self, 0, 1, PipeShape::Horizontal);
-                }
-                PipeShape::SouthEast => {
-                    set(&mut grid, self, 1, 2, PipeShape::Vertical);
-                    set(&mut grid, self, 2, 1, PipeShape::Horizontal);
-                }
-                PipeShape::SouthWest => {
-                    set(&mut grid, self, 1, 2, PipeShape::Vertical);
-                    set(&mut grid, self, 0, 1, PipeShape::Horizontal);
-                }
-            }
-
-            grid
+                .directions()
+                .into_iter()
+                .find(|&next| next != direction.opposite())
+                .unwrap();
+            point = point + direction;
         }
-    }
-
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub enum PipeState {
-        PartOfLoop,
-        OutsideLoop,
-        Unknown,
+        pipe_loop
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GridTile)]
 pub enum PipeShape {
+    #[tile('.')]
     Empty,
+    #[tile(marker = 'S', display = 'S')]
     Start,
+    #[tile('|', display = '│')]
     Vertical,
+    #[tile('-', display = '─')]
     Horizontal,
+    #[tile('L', display = '└')]
     NorthEast,
+    #[tile('J', display = '┘')]
     NorthWest,
+    #[tile('F', display = '┌')]
     SouthEast,
+    #[tile('7', display = '┐')]
     SouthWest,
 }
 
 impl PipeShape {
-    fn from_directions(directions: &[Direction]) -> Self {
-        match directions {
-            [Direction::North, Direction::East] => PipeShape::NorthEast,
-            [Direction::North, Direction::West] => PipeShape::NorthWest,
-            [Direction::South, Direction::East] => PipeShape::SouthEast,
-            [Direction::South, Direction::West] => PipeShape::SouthWest,
-            [Direction::North, Direction::South] => PipeShape::Vertical,
-            [Direction::East, Direction::West] => PipeShape::Horizontal,
-            _ => panic!("Invalid directions: {:?}", directions),
-        }
-    }
-
     fn is_connected(&self, direction: Direction) -> bool {
         match self {
             PipeShape::Empty => false,
@@ -521,35 +237,3 @@ impl PipeShape {
         }
     }
 }
-
-impl From<char> for PipeShape {
-    fn from(c: char) -> Self {
-        match c {
-            '.' => PipeShape::Empty,
-            '|' => PipeShape::Vertical,
-            '-' => PipeShape::Horizontal,
-            'S' => PipeShape::Start,
-            'L' => PipeShape::NorthEast,
-            'J' => PipeShape::NorthWest,
-            '7' => PipeShape::SouthWest,
-            'F' => PipeShape::SouthEast,
-            _ => panic!("Invalid pipe character: {}", c),
-        }
-    }
-}
-
-impl Display for PipeShape {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let c = match self {
-            PipeShape::Empty => '.',
-            PipeShape::Start => 'S',
-            PipeShape::Vertical => '│',
-            PipeShape::Horizontal => '─',
-            PipeShape::NorthEast => '└',
-            PipeShape::NorthWest => '┘',
-            PipeShape::SouthWest => '┐',
-            PipeShape::SouthEast => '┌',
-        };
-        write!(f, "{}", c)
-    }
-}
@@ -32,7 +32,7 @@ pub fn part2(input: String) -> String {
         .to_string()
 }
 
-fn find_galaxy_points(map: &Array2D<Spot>, expansion: usize) -> Vec<Point> {
+fn find_galaxy_points(map: &Array2D<Spot>, expansion: i64) -> Vec<Point<i64>> {
     let rows = map
         .rows_iter()
         .map(|row| row.collect::<Vec<_>>())
@@ -52,7 +52,7 @@ fn find_galaxy_points(map: &Array2D<Spot>, expansion: usize) -> Vec<Point> {
         .collect()
 }
 
-fn line_expansion(lines: Vec<Vec<&Spot>>, expansion: usize) -> Vec<usize> {
+fn line_expansion(lines: Vec<Vec<&Spot>>, expansion: i64) -> Vec<i64> {
     lines
         .into_iter()
         .map(|line| match line.iter().all(|s| **s == Spot::Empty) {
@@ -66,11 +66,13 @@ fn line_expansion(lines: Vec<Vec<&Spot>>, expansion: usize) -> Vec<usize> {
         .collect::<Vec<_>>()
 }
 
-fn extrapolate_point(point: Point, row_expansions: &[usize], col_expansions: &[usize]) -> Point {
-    let x = point.x + col_expansions[point.x as usize] as i32;
-    let y = point.y + row_expansions[point.y as usize] as i32;
+fn extrapolate_point(point: Point, row_expansions: &[i64], col_expansions: &[i64]) -> Point<i64> {
+    let offset = Point::new(
+        col_expansions[point.x as usize],
+        row_expansions[point.y as usize],
+    );
 
-    Point::new(x, y)
+    point.convert::<i64>() + offset
 }
 
 fn parse_input(input: &str) -> Array2D<Spot> {
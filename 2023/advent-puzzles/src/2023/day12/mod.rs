@@ -1,7 +1,4 @@
-#![allow(unused_imports)]
-
-use crate::utils::memoize::{AtomicStats, MemoizeStats, MemoizeStatsProvider};
-use advent_derive::memoize;
+use crate::utils::dp::DpTable;
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 
 pub fn part1(input: String) -> String {
@@ -56,18 +53,23 @@ pub fn parse_input(input: &str) -> (&str, Vec<usize>) {
 }
 
 pub fn count_possible_assignments(assignment: &str, groups: &[usize]) -> usize {
-    count_assignments(assignment.to_string(), groups.to_vec(), 0, 0)
+    let mut table = DpTable::new([assignment.len() + 2, groups.len() + 1]);
+    count_assignments(assignment.as_bytes(), groups, 0, 0, &mut table)
 }
 
-#[memoize(key = (pattern, groups, char_index, group_index))]
 fn count_assignments(
-    pattern: String,
-    groups: Vec<usize>,
+    pattern: &[u8],
+    groups: &[usize],
     char_index: usize,
     group_index: usize,
+    table: &mut DpTable<usize, 2>,
 ) -> usize {
+    if let Some(&count) = table.get([char_index, group_index]) {
+        return count;
+    }
+
     if group_index == groups.len() {
-        if char_index >= pattern.len() || pattern[char_index..].chars().all(|c| c != '#') {
+        if char_index >= pattern.len() || pattern[char_index..].iter().all(|&c| c != b'#') {
             return 1;
         } else {
             return 0;
@@ -79,27 +81,29 @@ fn count_assignments(
     }
 
     let mut count = 0;
-    if pattern.chars().nth(char_index) != Some('#') {
-        count += count_assignments(pattern.clone(), groups.clone(), char_index + 1, group_index);
+    if pattern[char_index] != b'#' {
+        count += count_assignments(pattern, groups, char_index + 1, group_index, table);
     }
 
     let group_size = groups[group_index];
     let space_left = char_index + group_size <= pattern.len();
     let no_seperator = pattern
         [char_index..char_index.saturating_add(group_size).min(pattern.len())]
-        .chars()
-        .all(|c| c != '.');
-    let seperator_after = space_left && pattern.chars().nth(char_index + group_size) != Some('#');
+        .iter()
+        .all(|&c| c != b'.');
+    let seperator_after = space_left && pattern.get(char_index + group_size) != Some(&b'#');
 
     if space_left && no_seperator && seperator_after {
         count += count_assignments(
-            pattern.clone(),
-            groups.clone(),
+            pattern,
+            groups,
             char_index + group_size + 1,
             group_index + 1,
+            table,
         );
     }
 
+    table.insert([char_index, group_index], count);
     count
 }
 
@@ -116,7 +120,6 @@ mod tests {
     #[case("????.######..#####. 1,6,5", 4)]
     #[case("?###???????? 3,2,1", 10)]
     fn test_count_possible_assignments(#[case] input: &str, #[case] expected: usize) {
-        count_assignments_cache::clear();
         let (assignment, broken) = parse_input(input);
         let count = count_possible_assignments(assignment, &broken);
 
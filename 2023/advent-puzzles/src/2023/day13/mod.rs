@@ -2,6 +2,8 @@ use std::cmp::min;
 
 use itertools::Itertools;
 
+use crate::utils::{grid::Grid, map::GenerateMap};
+
 pub fn part1(input: String) -> String {
     let grids = parse_input(&input);
 
@@ -23,30 +25,25 @@ pub fn part2(input: String) -> String {
 }
 
 #[derive(Debug)]
-struct Grid {
+struct Pattern {
     collumns: Vec<String>,
     rows: Vec<String>,
 }
 
-fn parse_input(input: &str) -> Vec<Grid> {
+fn parse_input(input: &str) -> Vec<Pattern> {
     input.split("\n\n").map(|grid| parse_grid(grid)).collect()
 }
 
-fn parse_grid(grid: &str) -> Grid {
-    let rows: Vec<String> = grid.lines().map(|row| row.to_string()).collect();
+fn parse_grid(grid: &str) -> Pattern {
+    let grid = Grid::generate_map(grid, |_, c| c).expect("Pattern rows should be equally long");
 
-    let collumns = (0..rows[0].len())
-        .map(|i| {
-            rows.iter()
-                .map(|row| row.chars().nth(i).unwrap())
-                .collect::<String>()
-        })
-        .collect();
+    let rows = grid.rows().map(|row| row.iter().collect()).collect();
+    let collumns = grid.columns().map(|column| column.collect()).collect();
 
-    Grid { rows, collumns }
+    Pattern { rows, collumns }
 }
 
-impl Grid {
+impl Pattern {
     fn find_mirror_line(&self, smudges: usize) -> usize {
         if let Some(i) = find_mirror_line(&self.rows, smudges) {
             (i + 1) * 100
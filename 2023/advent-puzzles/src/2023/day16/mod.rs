@@ -5,12 +5,13 @@ use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 use crate::utils::{
     direction::Direction,
-    map::{GenerateMap, InteractWithPoint},
+    map::InteractWithPoint,
     point::Point,
+    tile::GridTile,
 };
 
 pub fn part1(input: String) -> String {
-    let grid: Array2D<Tile> = Array2D::generate_map(&input, |_, c| c.into()).unwrap();
+    let grid: Array2D<Tile> = Tile::parse_map(&input).unwrap().map;
 
     let start_beam = Beam::new(Direction::East, Point::new(0, 0));
     find_enerized_configuraton(&grid, start_beam).to_string()
@@ -18,7 +19,7 @@ pub fn part1(input: String) -> String {
 
 pub fn part2(input: String) -> String {
     // Find the highest energy configuration for all starting beams
-    let grid: Array2D<Tile> = Array2D::generate_map(&input, |_, c| c.into()).unwrap();
+    let grid: Array2D<Tile> = Tile::parse_map(&input).unwrap().map;
 
     let mut starting_beams = Vec::new();
 
@@ -194,24 +195,16 @@ impl Beam {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GridTile)]
 enum Tile {
+    #[tile('.')]
     Empty,
+    #[tile('|')]
     VerticalSplit,
+    #[tile('-')]
     HorizontalSplit,
+    #[tile('/')]
     ForwardMirror,
+    #[tile('\\')]
     BackwardMirror,
 }
-
-impl From<char> for Tile {
-    fn from(c: char) -> Self {
-        match c {
-            '.' => Self::Empty,
-            '|' => Self::VerticalSplit,
-            '-' => Self::HorizontalSplit,
-            '/' => Self::ForwardMirror,
-            '\\' => Self::BackwardMirror,
-            _ => panic!("Invalid tile: {}", c),
-        }
-    }
-}
@@ -1,10 +1,10 @@
 use array2d::Array2D;
-use pathfinding::directed::dijkstra::dijkstra;
 
 use crate::utils::{
     direction::Direction,
     map::{GenerateMap, InteractWithPoint},
     point::Point,
+    search::{dijkstra, State},
 };
 
 pub fn part1(input: String) -> String {
@@ -19,36 +19,26 @@ pub fn part2(input: String) -> String {
     find_shortest_path(&grid, 4, 10).unwrap().to_string()
 }
 
-fn find_shortest_path(grid: &Array2D<u32>, min_steps: u8, max_steps: u8) -> Option<u32> {
+fn find_shortest_path(grid: &Array2D<u32>, min_steps: u8, max_steps: u8) -> Option<usize> {
     let end_point = Point::new(grid.num_columns() as i32 - 1, grid.num_rows() as i32 - 1);
-
-    let (_, c) = dijkstra(
-        &PathNode::new(Point::new(0, 0), Direction::South, 0),
-        |path_node| {
-            let map = |p: PathNode| {
-                let cost = *grid.get_point(&p.point).unwrap();
-                (p, cost)
-            };
-            if path_node.steps == 0 {
-                return vec![
-                    PathNode::new(Point::new(0, 1), Direction::South, 1),
-                    PathNode::new(Point::new(1, 0), Direction::East, 1),
-                ]
-                .into_iter()
-                .map(map)
-                .into_iter();
-            }
-
-            path_node
-                .generate_next_nodes(&grid, min_steps, max_steps)
-                .into_iter()
-                .map(map)
-                .into_iter()
-        },
+    let crucible = Crucible {
+        grid,
+        min_steps,
+        max_steps,
+    };
+
+    dijkstra(
+        [PathNode::new(Point::new(0, 0), Direction::South, 0)],
+        &crucible,
         |looking| looking.point == end_point && looking.steps >= min_steps,
-    )?;
+    )
+    .target_distance()
+}
 
-    Some(c)
+struct Crucible<'a> {
+    grid: &'a Array2D<u32>,
+    min_steps: u8,
+    max_steps: u8,
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -103,3 +93,24 @@ impl PathNode {
         .collect()
     }
 }
+
+impl State<Crucible<'_>> for PathNode {
+    fn successors(&self, crucible: &Crucible) -> Vec<(Self, usize)> {
+        let next_nodes = if self.steps == 0 {
+            vec![
+                PathNode::new(Point::new(0, 1), Direction::South, 1),
+                PathNode::new(Point::new(1, 0), Direction::East, 1),
+            ]
+        } else {
+            self.generate_next_nodes(crucible.grid, crucible.min_steps, crucible.max_steps)
+        };
+
+        next_nodes
+            .into_iter()
+            .map(|node| {
+                let cost = *crucible.grid.get_point(&node.point).unwrap();
+                (node, cost as usize)
+            })
+            .collect()
+    }
+}
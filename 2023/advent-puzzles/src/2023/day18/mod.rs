@@ -1,5 +1,3 @@
-use std::i64;
-
 use nom::{
     bytes::complete::{tag, take},
     character::complete::{digit1, newline},
@@ -7,7 +5,7 @@ use nom::{
     IResult,
 };
 
-use crate::utils::{direction::Direction, point::Point};
+use crate::utils::{direction::Direction, point::Point, polygon::Polygon};
 
 pub fn part1(input: String) -> String {
     let instructions = parse_input(&input).unwrap().1;
@@ -22,22 +20,14 @@ pub fn part2(input: String) -> String {
 }
 
 fn find_area(instructions: &[Instruction]) -> i64 {
-    let mut point = Point::new(0, 0);
-    let mut area: i64 = 0;
-    let mut perimeter: i64 = 0;
-
-    for instruction in instructions {
-        let new_point =
-            point.move_n_in_direction(instruction.direction, instruction.distance as i32 * 2);
-
-        area += point.x as i64 * new_point.y as i64 - new_point.x as i64 * point.y as i64;
-        perimeter += instruction.distance as i64 * 2;
-
-        point = new_point;
-    }
-
-    // Final area calculation
-    (area / 2 + perimeter) / 4 + 1
+    let lagoon = Polygon::from_instructions(
+        Point::<i64>::new(0, 0),
+        instructions
+            .iter()
+            .map(|instruction| (instruction.direction, instruction.distance as i64)),
+    );
+
+    lagoon.lattice_points()
 }
 
 fn parse_input(input: &str) -> IResult<&str, Vec<Instruction>> {
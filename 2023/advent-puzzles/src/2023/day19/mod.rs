@@ -1,4 +1,4 @@
-use std::{collections::HashMap, ops::Range, str::FromStr, u64};
+use std::{collections::HashMap, str::FromStr, u64};
 
 use nom::{
     bytes::complete::{tag, take},
@@ -7,6 +7,8 @@ use nom::{
     IResult, Parser,
 };
 
+use crate::utils::{hyper_box::HyperBox, interval::Interval};
+
 pub fn part1(input: String) -> String {
     let (parts, workflows) = parse_input(&input);
 
@@ -16,7 +18,7 @@ pub fn part1(input: String) -> String {
     let sections = find_allowed(
         &workflows.get("in").expect("No workflow named 'in'"),
         &workflows,
-        vec![],
+        all_ratings(),
     );
 
     parts
@@ -28,7 +30,8 @@ pub fn part1(input: String) -> String {
 }
 
 fn is_part_accepted(part: &Part, sections: &[Section]) -> bool {
-    sections.iter().any(|s| s.contains(part))
+    let ratings = [part.x, part.m, part.a, part.s].map(u64::from);
+    sections.iter().any(|s| s.contains(&ratings))
 }
 
 pub fn part2(input: String) -> String {
@@ -40,41 +43,41 @@ pub fn part2(input: String) -> String {
     let sections = find_allowed(
         &workflows.get("in").expect("No workflow named 'in'"),
         &workflows,
-        vec![],
+        all_ratings(),
     );
 
-    sections.iter().map(|s| s.count()).sum::<u64>().to_string()
+    sections.iter().map(|s| s.volume()).sum::<u64>().to_string()
+}
+
+/// Every combination of ratings, in the order x, m, a, s.
+type Section = HyperBox<4, u64>;
+
+fn all_ratings() -> Section {
+    HyperBox::new([Interval::new(1, 4001); 4])
 }
 
 fn find_allowed(
     workflow: &Workflow,
     workflows: &HashMap<String, Workflow>,
-    conditions: Vec<Condition>,
+    mut section: Section,
 ) -> Vec<Section> {
-    let allowed = workflow
-        .rules
-        .iter()
-        .enumerate()
-        .flat_map(|(index, rule)| {
-            let additional_condtions = workflow
-                .rules
-                .iter()
-                .take(index)
-                .flat_map(|r| match r {
-                    Rule::Condition { condition, .. } => Some(condition.invert()),
-                    _ => None,
-                })
-                .collect::<Vec<_>>();
-
-            let mut new_conditions = conditions.clone();
-            new_conditions.extend(additional_condtions);
-            if let Rule::Condition { condition, .. } = rule {
-                new_conditions.push(condition.clone());
-            }
+    let mut allowed = vec![];
+
+    for rule in &workflow.rules {
+        let (matching, rest) = match rule {
+            Rule::Condition { condition, .. } => condition.split(&section),
+            Rule::Direct { .. } => (Some(section), None),
+        };
+
+        if let Some(matching) = matching {
+            allowed.extend(find_allowed_for_target(rule.target(), workflows, matching));
+        }
 
-            find_allowed_for_target(rule.target(), workflows, new_conditions)
-        })
-        .collect::<Vec<_>>();
+        match rest {
+            Some(rest) => section = rest,
+            None => break,
+        }
+    }
 
     allowed
 }
@@ -82,76 +85,15 @@ fn find_allowed(
 fn find_allowed_for_target(
     target: &Target,
     workflows: &HashMap<String, Workflow>,
-    conditions: Vec<Condition>,
+    section: Section,
 ) -> Vec<Section> {
     match target {
         Target::Workflow(name) => {
             let workflow = workflows.get(name).expect("No workflow with that name");
-            find_allowed(workflow, workflows, conditions)
+            find_allowed(workflow, workflows, section)
         }
         Target::Rejected => vec![],
-        Target::Accepted => vec![find_allowed_with_conditions(&conditions)],
-    }
-}
-
-fn find_allowed_with_conditions(conditions: &[Condition]) -> Section {
-    let x = find_allowed_with_conditions_in_category(conditions, Category::X);
-    let m = find_allowed_with_conditions_in_category(conditions, Category::M);
-    let a = find_allowed_with_conditions_in_category(conditions, Category::A);
-    let s = find_allowed_with_conditions_in_category(conditions, Category::S);
-
-    Section { x, m, a, s }
-}
-
-fn find_allowed_with_conditions_in_category(
-    conditions: &[Condition],
-    category: Category,
-) -> Range<u16> {
-    let applicable_conditions = conditions
-        .iter()
-        .filter(|c| c.category == category)
-        .collect::<Vec<_>>();
-
-    let min = applicable_conditions
-        .iter()
-        .filter(|c| c.operator == Operator::GreaterThan)
-        .map(|c| c.value + 1)
-        .max()
-        .unwrap_or(1);
-
-    let max = applicable_conditions
-        .iter()
-        .filter(|c| c.operator == Operator::LessThan)
-        .map(|c| c.value)
-        .min()
-        .unwrap_or(4001);
-
-    min..max
-}
-
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct Section {
-    x: Range<u16>,
-    m: Range<u16>,
-    a: Range<u16>,
-    s: Range<u16>,
-}
-
-impl Section {
-    fn contains(&self, part: &Part) -> bool {
-        self.x.contains(&part.x)
-            && self.m.contains(&part.m)
-            && self.a.contains(&part.a)
-            && self.s.contains(&part.s)
-    }
-
-    fn count(&self) -> u64 {
-        let x: u64 = self.x.end as u64 - self.x.start as u64;
-        let m: u64 = self.m.end as u64 - self.m.start as u64;
-        let a: u64 = self.a.end as u64 - self.a.start as u64;
-        let s: u64 = self.s.end as u64 - self.s.start as u64;
-
-        x * m * a * s
+        Target::Accepted => vec![section],
     }
 }
 
@@ -199,18 +141,17 @@ struct Condition {
 }
 
 impl Condition {
-    fn invert(&self) -> Self {
-        let new_operator = match self.operator {
-            Operator::LessThan => Operator::GreaterThan,
-            Operator::GreaterThan => Operator::LessThan,
-        };
-        Self {
-            category: self.category.clone(),
-            operator: new_operator,
-            value: match self.operator {
-                Operator::LessThan => self.value - 1,
-                Operator::GreaterThan => self.value + 1,
-            },
+    /// Splits `section` into the part that matches the condition and the
+    /// part that falls through to the next rule.
+    fn split(&self, section: &Section) -> (Option<Section>, Option<Section>) {
+        let axis = self.category.clone() as usize;
+        let value = u64::from(self.value);
+        match self.operator {
+            Operator::LessThan => section.split_at(axis, value),
+            Operator::GreaterThan => {
+                let (rest, matching) = section.split_at(axis, value + 1);
+                (matching, rest)
+            }
         }
     }
 }
@@ -1,26 +1,17 @@
-use std::{collections::HashMap, fmt::Display, hash::Hasher};
-
 use array2d::Array2D;
-use itertools::Itertools;
-use pathfinding::directed::astar::astar;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 use crate::utils::{
-    map::{GenerateMap, InteractWithPoint},
+    map::InteractWithPoint,
     point::Point,
+    search::{bfs, State},
+    tile::{GridTile, TileMap},
 };
 
 pub fn part1(input: String) -> String {
-    let mut start = Point::default();
-    let map = Array2D::generate_map(&input, |p, c| {
-        if c == 'S' {
-            start = p;
-            Tile::GardenPlot
-        } else {
-            Tile::from(c)
-        }
-    })
-    .expect("Failed to generate map");
+    let TileMap { map, markers } =
+        Tile::parse_map::<Array2D<_>>(&input).expect("Failed to generate map");
+    let start = markers[&'S'][0];
 
     let steps = 64;
 
@@ -28,16 +19,9 @@ pub fn part1(input: String) -> String {
 }
 
 pub fn part2(input: String) -> String {
-    let mut start = Point::default();
-    let map = Array2D::generate_map(&input, |p, c| {
-        if c == 'S' {
-            start = p;
-            Tile::GardenPlot
-        } else {
-            Tile::from(c)
-        }
-    })
-    .expect("Failed to generate map");
+    let TileMap { map, markers } =
+        Tile::parse_map::<Array2D<_>>(&input).expect("Failed to generate map");
+    let start = markers[&'S'][0];
 
     let height = map.num_rows() as i32;
     let target = 26501365;
@@ -70,121 +54,40 @@ pub fn part2(input: String) -> String {
 }
 
 fn calculate_plots(map: &Array2D<Tile>, steps: i32, start_point: &Point) -> i64 {
-    let mut cache = HashMap::new();
-    cache.insert(start_point.clone(), 0);
-
-    (-steps..=steps)
-        .flat_map(|y| {
-            let diff = steps - y.abs();
-            (-diff..=diff)
-                .step_by(2)
-                .map(move |x| Point::new(start_point.x + x, start_point.y + y))
-        })
-        .sorted_by(|a, b| {
-            a.manhattan_distance(start_point)
-                .cmp(&b.manhattan_distance(start_point))
-        })
-        .filter(|p| validate_point(map, p, steps, start_point, &mut cache))
+    let garden = Garden {
+        map,
+        start: *start_point,
+        steps: steps as usize,
+    };
+    bfs([*start_point], &garden, |_| false)
+        .distances()
+        .filter(|&(_, distance)| distance <= garden.steps && distance % 2 == garden.steps % 2)
         .count() as i64
 }
 
-fn validate_point(
-    map: &Array2D<Tile>,
-    point: &Point,
-    steps: i32,
-    start_point: &Point,
-    cache: &mut HashMap<Point, i32>,
-) -> bool {
-    if *map.get_looping_point(point) != Tile::GardenPlot {
-        return false;
-    }
-
-    let Some((path, cost)) = astar(
-        &PathPoint::new(*point, 0),
-        |pp| {
-            pp.point
-                .neighbours()
-                .iter()
-                .filter(|neighbour| {
-                    let y = neighbour.y - start_point.y;
-                    let diff = steps - y.abs();
-                    let x = neighbour.x - start_point.x;
-                    x.abs() <= diff
-                })
-                .filter(|p| *map.get_looping_point(p) == Tile::GardenPlot)
-                .map(|p| (PathPoint::new(*p, pp.cost + 1), 1))
-                .collect::<Vec<_>>()
-        },
-        |pp| pp.point.manhattan_distance(start_point),
-        |pp| {
-            if pp.point == *start_point {
-                return true;
-            }
-
-            if let Some(c) = cache.get(&pp.point) {
-                return *c + pp.cost <= steps;
-            }
-            false
-        },
-    ) else {
-        return false;
-    };
-
-    let last = path.last().unwrap().point;
-    let last_cost = cache.get(&last).unwrap();
-    let true_cost = last_cost + cost as i32;
-
-    cache.insert(point.clone(), true_cost);
+/// The infinitely repeating garden, limited to the plots that could be reached
+/// within `steps`.
+struct Garden<'a> {
+    map: &'a Array2D<Tile>,
+    start: Point,
+    steps: usize,
+}
 
-    true_cost <= steps
+impl State<Garden<'_>> for Point {
+    fn successors(&self, garden: &Garden) -> Vec<(Self, usize)> {
+        self.neighbours()
+            .into_iter()
+            .filter(|p| p.manhattan_distance(&garden.start) <= garden.steps)
+            .filter(|p| *garden.map.get_looping_point(p) == Tile::GardenPlot)
+            .map(|p| (p, 1))
+            .collect()
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GridTile)]
 enum Tile {
+    #[tile('.', marker = 'S')]
     GardenPlot,
+    #[tile('#')]
     Rock,
 }
-
-impl From<char> for Tile {
-    fn from(c: char) -> Self {
-        match c {
-            '#' => Tile::Rock,
-            '.' => Tile::GardenPlot,
-            _ => panic!("Invalid tile"),
-        }
-    }
-}
-
-impl Display for Tile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let c = match self {
-            Tile::GardenPlot => '.',
-            Tile::Rock => '#',
-        };
-        write!(f, "{}", c)
-    }
-}
-
-#[derive(Debug, Clone, Copy, Eq)]
-struct PathPoint {
-    point: Point,
-    cost: i32,
-}
-
-impl PathPoint {
-    fn new(point: Point, cost: i32) -> Self {
-        Self { point, cost }
-    }
-}
-
-impl PartialEq for PathPoint {
-    fn eq(&self, other: &Self) -> bool {
-        self.point == other.point
-    }
-}
-
-impl std::hash::Hash for PathPoint {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.point.hash(state);
-    }
-}
@@ -1,9 +1,7 @@
-use std::u16;
-
 use nom::{bytes::complete::tag, character::complete::newline, multi::separated_list1, IResult};
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use crate::utils::point_3d::Point3D;
+use crate::utils::point_3d::{Aabb3D, Point3D};
 
 pub fn part1(input: String) -> String {
     let mut bricks = parse_bricks(&input).unwrap().1;
@@ -97,15 +95,15 @@ impl Brick {
             .all(|brick| !bottom_slice.intersects(brick))
     }
 
+    fn bounds(&self) -> Aabb3D<u16> {
+        Aabb3D {
+            min: self.min_corner,
+            max: self.max_corner,
+        }
+    }
+
     fn intersects(&self, other: &Self) -> bool {
-        let x_overlap =
-            self.min_corner.x <= other.max_corner.x && self.max_corner.x >= other.min_corner.x;
-        let y_overlap =
-            self.min_corner.y <= other.max_corner.y && self.max_corner.y >= other.min_corner.y;
-        let z_overlap =
-            self.min_corner.z <= other.max_corner.z && self.max_corner.z >= other.min_corner.z;
-
-        x_overlap && y_overlap && z_overlap
+        self.bounds().intersects(&other.bounds())
     }
 
     fn bottom_slice(&self) -> Brick {
@@ -1,4 +1,3 @@
-use std::ops::{self};
 use std::u64;
 
 use itertools::Itertools;
@@ -8,6 +7,8 @@ use nom::{
 };
 use strum_macros::Display;
 
+use crate::utils::interval::{Interval, IntervalSet, RangeMap};
+
 pub fn part1(input: String) -> String {
     let (group, mappings) = parse(&input);
 
@@ -29,33 +30,13 @@ fn transform(group: Group, mappings: &[Mapping]) -> Group {
         return group;
     };
 
-    transform_mapping(group, mapper)
-}
-
-fn transform_mapping(group: Group, mapping: &Mapping) -> Group {
-    let mut old_values = group.values.clone();
-    let mut values = vec![];
-
-    for range in &mapping.ranges {
-        let matching_values = old_values
-            .iter()
-            .filter(|value| range.contains(**value))
-            .collect::<Vec<_>>();
-
-        values.extend(
-            matching_values
-                .iter()
-                .map(|value| range.map(**value).unwrap()),
-        );
-
-        old_values.retain(|value| !range.contains(*value));
-    }
-
-    values.extend(old_values);
-
     Group {
-        type_: mapping.to.clone(),
-        values,
+        type_: mapper.to.clone(),
+        values: group
+            .values
+            .iter()
+            .map(|value| mapper.ranges.map(*value))
+            .collect(),
     }
 }
 
@@ -67,11 +48,8 @@ pub fn part2(input: String) -> String {
         .values
         .iter()
         .tuples()
-        .map(|(start, length)| ops::Range {
-            start: *start,
-            end: *start + *length,
-        })
-        .collect::<Vec<_>>();
+        .map(|(start, length)| Interval::with_length(*start, *length))
+        .collect();
 
     let mut group = GroupRange {
         type_: group.type_,
@@ -80,104 +58,20 @@ pub fn part2(input: String) -> String {
 
     while group.type_ != Type::Location {
         if let Some(mapper) = find_mapper(group.type_.clone(), &mappings) {
-            group = transform_range_mapping(group, mapper);
+            group = GroupRange {
+                type_: mapper.to.clone(),
+                ranges: mapper.ranges.map_set(&group.ranges),
+            };
         } else {
             break;
         }
     }
 
-    let min = group
-        .ranges
-        .iter()
-        .map(|range| range.start)
-        .min()
-        .unwrap_or(u64::MAX);
+    let min = group.ranges.min().unwrap_or(u64::MAX);
 
     format!("{}", min)
 }
 
-fn transform_range_mapping(group: GroupRange, mapping: &Mapping) -> GroupRange {
-    let ranges = group
-        .ranges
-        .iter()
-        .flat_map(|range| transform_range(range, mapping).into_iter())
-        .collect::<Vec<_>>();
-
-    GroupRange {
-        type_: mapping.to.clone(),
-        ranges,
-    }
-}
-
-fn transform_range(range: &ops::Range<u64>, mapping: &Mapping) -> Vec<ops::Range<u64>> {
-    let intersections = mapping
-        .ranges
-        .iter()
-        .filter_map(|mapping_range| mapping_range.intersection(&range))
-        .collect::<Vec<_>>();
-
-    // All the ranges withing the range that are not mapped should be kept.
-    let mut ranges = vec![];
-
-    let mut start = range.start;
-    let flattened = flatten_ranges(
-        intersections
-            .iter()
-            .map(|(a, _)| a.clone())
-            .collect::<Vec<_>>(),
-    );
-    for intersection in flattened {
-        if start < intersection.start {
-            ranges.push(ops::Range {
-                start,
-                end: intersection.start,
-            });
-        }
-        start = intersection.end;
-    }
-
-    if start < range.end {
-        ranges.push(ops::Range {
-            start,
-            end: range.end,
-        });
-    }
-
-    ranges.extend(
-        intersections
-            .iter()
-            .map(|(_, b)| b.clone())
-            .collect::<Vec<_>>(),
-    );
-
-    ranges
-}
-
-fn flatten_ranges(ranges: Vec<ops::Range<u64>>) -> Vec<ops::Range<u64>> {
-    let ranges = ranges
-        .iter()
-        .sorted_by(|a, b| a.start.cmp(&b.start))
-        .collect::<Vec<_>>();
-
-    let mut flattened: Vec<ops::Range<u64>> = vec![];
-
-    for range in ranges {
-        let current = match flattened.last_mut() {
-            Some(last) => {
-                if last.overlaps(range) {
-                    last.merge_range(range);
-                    continue;
-                }
-                range.clone()
-            }
-            None => range.clone(),
-        };
-        flattened.push(current);
-    }
-
-    flattened
-}
-
 fn find_mapper(type_: Type, mappings: &[Mapping]) -> Option<&Mapping> {
     mappings.iter().find(|mapping| mapping.from == type_)
 }
@@ -221,8 +115,7 @@ fn parse_mapping(input: &str) -> IResult<&str, Mapping> {
     let ranges = lines[1..]
         .iter()
         .map(|line| parse_range(line).unwrap().1)
-        .sorted()
-        .collect::<Vec<_>>();
+        .collect::<RangeMap<_>>();
 
     Ok((input, Mapping { from, to, ranges }))
 }
@@ -235,7 +128,7 @@ fn parse_source_destination(input: &str) -> IResult<&str, (Type, Type)> {
     Ok((input, (from, to)))
 }
 
-fn parse_range(input: &str) -> IResult<&str, Range> {
+fn parse_range(input: &str) -> IResult<&str, (Interval<u64>, u64)> {
     let (input, numbers) = separated_list1(tag(" "), digit1)(input)?;
 
     let numbers = numbers
@@ -248,14 +141,7 @@ fn parse_range(input: &str) -> IResult<&str, Range> {
         _ => panic!("Invalid range: {:?}", numbers),
     };
 
-    Ok((
-        input,
-        Range {
-            source,
-            destination,
-            length,
-        },
-    ))
+    Ok((input, (Interval::with_length(source, length), destination)))
 }
 
 fn parse_type(input: &str) -> IResult<&str, Type> {
@@ -280,61 +166,14 @@ struct Group {
 #[derive(Debug, Clone)]
 struct GroupRange {
     type_: Type,
-    ranges: Vec<ops::Range<u64>>,
+    ranges: IntervalSet<u64>,
 }
 
 #[derive(Debug, Clone)]
 struct Mapping {
     from: Type,
     to: Type,
-    ranges: Vec<Range>,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Ord)]
-struct Range {
-    source: u64,
-    destination: u64,
-    length: u64,
-}
-
-impl Range {
-    fn contains(&self, value: u64) -> bool {
-        value >= self.source && value <= self.source + self.length
-    }
-
-    fn map(&self, value: u64) -> Option<u64> {
-        if !self.contains(value) {
-            return None;
-        }
-        let offset = value - self.source;
-        Some(self.destination + offset)
-    }
-
-    fn intersection(&self, range: &ops::Range<u64>) -> Option<(ops::Range<u64>, ops::Range<u64>)> {
-        let start = std::cmp::max(self.source, range.start);
-        let end = std::cmp::min(self.source + self.length, range.end);
-
-        if start > end {
-            return None;
-        }
-
-        let source = (start - self.source) + self.destination;
-        let destination = (end - self.source) + self.destination;
-
-        Some((
-            ops::Range { start, end },
-            ops::Range {
-                start: source,
-                end: destination,
-            },
-        ))
-    }
-}
-
-impl PartialOrd for Range {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.source.partial_cmp(&other.source)
-    }
+    ranges: RangeMap<u64>,
 }
 
 #[derive(Debug, Clone, Display, PartialEq, Eq)]
@@ -349,39 +188,16 @@ enum Type {
     Location,
 }
 
-trait RangeExt {
-    fn overlaps(&self, other: &ops::Range<u64>) -> bool;
-    fn merge_range(&mut self, other: &ops::Range<u64>);
-}
-
-impl RangeExt for ops::Range<u64> {
-    fn overlaps(&self, other: &ops::Range<u64>) -> bool {
-        self.start <= other.end && self.end >= other.start
-            || other.start <= self.end && other.end >= self.start
-    }
-
-    fn merge_range(&mut self, other: &ops::Range<u64>) {
-        self.start = std::cmp::min(self.start, other.start);
-        self.end = std::cmp::max(self.end, other.end);
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_flatten_range() {
-        let ranges = vec![
-            ops::Range { start: 0, end: 10 },
-            ops::Range { start: 3, end: 7 },
-            ops::Range { start: 7, end: 12 },
-            ops::Range { start: 15, end: 20 },
-            ops::Range { start: 11, end: 16 },
-        ];
+        let ranges = vec![0..10, 3..7, 7..12, 15..20, 11..16];
 
-        let flattened = flatten_ranges(ranges);
+        let flattened = ranges.into_iter().collect::<IntervalSet<u64>>();
 
-        assert_eq!(flattened, vec![ops::Range { start: 0, end: 20 },]);
+        assert_eq!(flattened.intervals(), &[Interval::new(0, 20)]);
     }
 }
@@ -10,7 +10,7 @@ use nom::{
 };
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::utils::point::Point;
+use crate::utils::{grid::Grid, point::Point};
 
 #[derive(Debug, Clone)]
 pub struct ParsedShape {
@@ -99,23 +99,11 @@ struct ShapeOrientation {
 }
 
 impl ShapeOrientation {
-    fn from_grid(grid: &[Vec<bool>]) -> Self {
-        let height = grid.len();
-        let width = if height > 0 { grid[0].len() } else { 0 };
-
-        let mut offsets = Vec::new();
-        for (r, row) in grid.iter().enumerate() {
-            for (c, &occupied) in row.iter().enumerate() {
-                if occupied {
-                    offsets.push(Point::new(c as i32, r as i32));
-                }
-            }
-        }
-
+    fn from_grid(grid: &Grid<bool>) -> Self {
         ShapeOrientation {
-            offsets,
-            width,
-            height,
+            offsets: grid.find_all(|&occupied| occupied).collect(),
+            width: grid.width(),
+            height: grid.height(),
         }
     }
 
@@ -126,35 +114,19 @@ impl ShapeOrientation {
 
 impl Shape {
     fn new(parsed_shape: &ParsedShape) -> Self {
-        let grid = parsed_shape.grid.clone();
-        let occupied_spaces = grid.iter().flatten().filter(|&&b| b).count();
+        let grid = Grid::from_rows(&parsed_shape.grid).expect("Shape rows should be equally long");
+        let occupied_spaces = grid.count(|&b| b);
 
         let mut orientations = Vec::new();
 
-        let mut current = grid.clone();
-
-        for _ in 0..4 {
-            let orientation = ShapeOrientation::from_grid(&current);
-            if !orientations
-                .iter()
-                .any(|o: &ShapeOrientation| o.is_same(&orientation))
-            {
-                orientations.push(orientation);
-            }
-            current = rotate_90(&current);
-        }
-
-        current = flip_horizontal(&grid);
-
-        for _ in 0..4 {
-            let orientation = ShapeOrientation::from_grid(&current);
+        for oriented in grid.orientations() {
+            let orientation = ShapeOrientation::from_grid(&oriented);
             if !orientations
                 .iter()
                 .any(|o: &ShapeOrientation| o.is_same(&orientation))
             {
                 orientations.push(orientation);
             }
-            current = rotate_90(&current);
         }
 
         Shape {
@@ -164,27 +136,6 @@ impl Shape {
     }
 }
 
-fn rotate_90(grid: &[Vec<bool>]) -> Vec<Vec<bool>> {
-    if grid.is_empty() {
-        return vec![];
-    }
-    let rows = grid.len();
-    let cols = grid[0].len();
-    let mut rotated = vec![vec![false; rows]; cols];
-    for r in 0..rows {
-        for c in 0..cols {
-            rotated[c][rows - 1 - r] = grid[r][c];
-        }
-    }
-    rotated
-}
-
-fn flip_horizontal(grid: &[Vec<bool>]) -> Vec<Vec<bool>> {
-    grid.iter()
-        .map(|row| row.iter().rev().cloned().collect())
-        .collect()
-}
-
 pub fn part1(input: String) -> String {
     let (_, input) = parse_input(&input).expect("Could not parse input");
 
@@ -103,7 +103,7 @@ mod tests {
 
     #[test]
     fn range_1698522_1698528_has_no_invalid() {
-        assert_eq!(invalid_ids_in_range(1698522..=1698528), vec![]);
+        assert_eq!(invalid_ids_in_range(1698522..=1698528), Vec::<u64>::new());
     }
 
     #[test]
@@ -118,16 +118,16 @@ mod tests {
 
     #[test]
     fn range_565653_565659_has_no_invalid() {
-        assert_eq!(invalid_ids_in_range(565653..=565659), vec![]);
+        assert_eq!(invalid_ids_in_range(565653..=565659), Vec::<u64>::new());
     }
 
     #[test]
     fn range_824824821_824824827_has_no_invalid() {
-        assert_eq!(invalid_ids_in_range(824824821..=824824827), vec![]);
+        assert_eq!(invalid_ids_in_range(824824821..=824824827), Vec::<u64>::new());
     }
 
     #[test]
     fn range_2121212118_2121212124_has_no_invalid() {
-        assert_eq!(invalid_ids_in_range(2121212118..=2121212124), vec![]);
+        assert_eq!(invalid_ids_in_range(2121212118..=2121212124), Vec::<u64>::new());
     }
 }
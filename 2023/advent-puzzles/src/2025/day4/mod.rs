@@ -1,22 +1,23 @@
 use crate::utils::{
-    map::{GenerateMap, InteractWithPoint, IterAll},
+    map::{InteractWithPoint, IterAll},
     point::Point,
+    region::Connectivity,
+    tile::GridTile,
 };
 use array2d::Array2D;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GridTile)]
 enum Spot {
+    #[tile('.')]
     Empty,
+    #[tile('@')]
     Roll,
 }
 
 pub fn part1(input: String) -> String {
-    let map = Array2D::generate_map(&input, |_, char| match char {
-        '.' => Spot::Empty,
-        '@' => Spot::Roll,
-        _ => panic!("Invalid character"),
-    })
-    .expect("Failed to generate map");
+    let map = Spot::parse_map::<Array2D<_>>(&input)
+        .expect("Failed to generate map")
+        .map;
 
     map.iter_all()
         .filter(|(point, spot)| **spot == Spot::Roll && is_accessable(&map, point))
@@ -25,7 +26,8 @@ pub fn part1(input: String) -> String {
 }
 
 fn is_accessable(map: &Array2D<Spot>, point: &Point) -> bool {
-    map.eight_neighbours(point)
+    Connectivity::Eight
+        .neighbours(map, point)
         .iter()
         .filter(
             |neighbour| match map.get_point(neighbour).expect("Invalid neighbour") {
@@ -38,12 +40,9 @@ fn is_accessable(map: &Array2D<Spot>, point: &Point) -> bool {
 }
 
 pub fn part2(input: String) -> String {
-    let mut map = Array2D::generate_map(&input, |_, char| match char {
-        '.' => Spot::Empty,
-        '@' => Spot::Roll,
-        _ => panic!("Invalid character"),
-    })
-    .expect("Failed to generate map");
+    let mut map = Spot::parse_map::<Array2D<_>>(&input)
+        .expect("Failed to generate map")
+        .map;
 
     let mut count = 0;
     loop {
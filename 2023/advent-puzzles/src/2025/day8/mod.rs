@@ -1,51 +1,6 @@
 use rayon::prelude::*;
 
-use crate::utils::point_3d::Point3D;
-
-struct UnionFind {
-    parent: Vec<usize>,
-    rank: Vec<usize>,
-    size: Vec<usize>,
-}
-
-impl UnionFind {
-    fn new(n: usize) -> Self {
-        UnionFind {
-            parent: (0..n).collect(),
-            rank: vec![0; n],
-            size: vec![1; n],
-        }
-    }
-
-    fn find(&mut self, x: usize) -> usize {
-        if self.parent[x] != x {
-            self.parent[x] = self.find(self.parent[x]);
-        }
-        self.parent[x]
-    }
-
-    fn union(&mut self, x: usize, y: usize) -> bool {
-        let root_x = self.find(x);
-        let root_y = self.find(y);
-
-        if root_x == root_y {
-            return false;
-        }
-
-        if self.rank[root_x] < self.rank[root_y] {
-            self.parent[root_x] = root_y;
-            self.size[root_y] += self.size[root_x];
-        } else if self.rank[root_x] > self.rank[root_y] {
-            self.parent[root_y] = root_x;
-            self.size[root_x] += self.size[root_y];
-        } else {
-            self.parent[root_y] = root_x;
-            self.size[root_x] += self.size[root_y];
-            self.rank[root_x] += 1;
-        }
-        true
-    }
-}
+use crate::utils::{dsu::Dsu, point_3d::Point3D};
 
 #[inline(always)]
 fn parse_i64(s: &[u8]) -> i64 {
@@ -84,14 +39,6 @@ fn parse_points(input: &str) -> Vec<Point3D<i64>> {
         .collect()
 }
 
-#[inline(always)]
-fn distance_squared(a: &Point3D<i64>, b: &Point3D<i64>) -> i64 {
-    let dx = a.x - b.x;
-    let dy = a.y - b.y;
-    let dz = a.z - b.z;
-    dx * dx + dy * dy + dz * dz
-}
-
 #[inline(always)]
 fn edge_index_to_pair(edge_idx: usize, n: usize) -> (usize, usize) {
     let k = edge_idx as f64;
@@ -110,7 +57,7 @@ fn compute_edges(points: &[Point3D<i64>]) -> Vec<(usize, usize, i64)> {
         .into_par_iter()
         .map(|edge_idx| {
             let (i, j) = edge_index_to_pair(edge_idx, n);
-            let distance = distance_squared(&points[i], &points[j]);
+            let distance = points[i].distance_squared(&points[j]);
             (i, j, distance)
         })
         .collect()
@@ -131,29 +78,13 @@ pub fn part1(input: String) -> String {
         joins.sort_unstable_by_key(|&(_, _, d)| d);
     }
 
-    let mut uf = UnionFind::new(n);
+    let mut dsu = Dsu::new(n);
 
     for &(i, j, _) in joins.iter().take(num_connections) {
-        uf.union(i, j);
-    }
-
-    let mut seen_roots = vec![false; n];
-    let mut sizes: Vec<usize> = Vec::new();
-
-    for i in 0..n {
-        let root = uf.find(i);
-        if !seen_roots[root] {
-            seen_roots[root] = true;
-            sizes.push(uf.size[root]);
-        }
+        dsu.union(i, j);
     }
 
-    if sizes.is_empty() {
-        return "0".to_string();
-    }
-
-    sizes.sort_unstable_by(|a, b| b.cmp(a));
-    let product: usize = sizes.iter().take(3).product();
+    let product: usize = dsu.largest(3).iter().product();
     product.to_string()
 }
 
@@ -164,16 +95,12 @@ pub fn part2(input: String) -> String {
 
     joins.par_sort_unstable_by_key(|&(_, _, d)| d);
 
-    let mut uf = UnionFind::new(n);
-    let mut num_components = n;
+    let mut dsu = Dsu::new(n);
 
     for &(i, j, _) in &joins {
-        if uf.union(i, j) {
-            num_components -= 1;
-            if num_components == 1 {
-                let product = points[i].x * points[j].x;
-                return product.to_string();
-            }
+        if dsu.union(i, j) && dsu.component_count() == 1 {
+            let product = points[i].x * points[j].x;
+            return product.to_string();
         }
     }
 
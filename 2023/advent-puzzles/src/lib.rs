@@ -4,10 +4,27 @@ use advent_derive::*;
 
 pub mod utils;
 
-pub use utils::memoize::{clear_all_caches_and_stats, print_memoize_stats};
+pub use utils::memoize::{clear_all_caches_and_stats, print_memoize_stats, MemoScope};
 
 year_declerations!();
 
+/// Runs a single part inside a fresh [`MemoScope`], so memoized results never
+/// leak between calls. The part runs in a rayon pool whose workers share the
+/// scope. When a persist directory is set, persisted caches are loaded for
+/// this `year`, `day` and `input` and saved again afterwards.
 pub fn run_day(year: u16, day: u8, part: bool, input: String) -> String {
-    day_invocations!(year, day, part, input)
+    let context = utils::memoize::persist_dir()
+        .map(|dir| utils::memoize::PersistContext::new(year, day, &input).in_dir(dir));
+    let persist = context.is_some();
+    let _scope = context.map_or_else(MemoScope::new, MemoScope::persisted);
+    if persist {
+        utils::memoize::load_persisted_caches();
+    }
+
+    let result = MemoScope::current().install(move || day_invocations!(year, day, part, input));
+
+    if persist {
+        utils::memoize::save_persisted_caches();
+    }
+    result
 }
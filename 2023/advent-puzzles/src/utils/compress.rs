@@ -0,0 +1,325 @@
+use num::PrimInt;
+
+use super::{
+    grid::Grid,
+    grid_3d::Grid3D,
+    point::{Coordinate, Point},
+    point_3d::Point3D,
+};
+
+/// One compressed axis. Every distinct input coordinate gets its own cell,
+/// and with gaps enabled the run of coordinates between two inputs becomes a
+/// single extra cell, so regions stay separated the way they are in the
+/// uncompressed plane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAxis<T = i32> {
+    /// First real coordinate of each cell, strictly increasing.
+    starts: Vec<T>,
+    /// Number of real coordinates in each cell.
+    sizes: Vec<T>,
+}
+
+impl<T: PrimInt> CompressedAxis<T> {
+    pub fn new<I: IntoIterator<Item = T>>(coordinates: I, gaps: bool) -> Self {
+        let mut coordinates = coordinates.into_iter().collect::<Vec<_>>();
+        coordinates.sort();
+        coordinates.dedup();
+
+        let mut axis = Self {
+            starts: Vec::with_capacity(coordinates.len() * 2),
+            sizes: Vec::with_capacity(coordinates.len() * 2),
+        };
+        for (i, &coordinate) in coordinates.iter().enumerate() {
+            axis.push(coordinate, T::one());
+            match coordinates.get(i + 1) {
+                Some(&next) if gaps && next - coordinate > T::one() => {
+                    axis.push(coordinate + T::one(), next - coordinate - T::one());
+                }
+                _ => {}
+            }
+        }
+        axis
+    }
+
+    fn push(&mut self, start: T, size: T) {
+        self.starts.push(start);
+        self.sizes.push(size);
+    }
+
+    /// Adds a cell of size one on both sides, so a flood fill from the first
+    /// cell can reach all the way around. A side is left unpadded if there is
+    /// no coordinate beyond it, like below zero for unsigned types.
+    pub fn padded(mut self) -> Self {
+        if let (Some(&first), Some(last)) = (self.starts.first(), self.len().checked_sub(1)) {
+            let end = self.start(last).checked_add(&self.size(last));
+            if let Some(before) = first.checked_sub(&T::one()) {
+                self.starts.insert(0, before);
+                self.sizes.insert(0, T::one());
+            }
+            if let Some(end) = end {
+                self.push(end, T::one());
+            }
+        }
+        self
+    }
+
+    /// Number of compressed cells.
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// The cell containing `value`, or `None` if it lies outside the axis or
+    /// in a gap that was not kept.
+    pub fn index(&self, value: T) -> Option<usize> {
+        let index = self.starts.partition_point(|&start| start <= value);
+        let index = index.checked_sub(1)?;
+        (value < self.start(index) + self.size(index)).then_some(index)
+    }
+
+    /// First real coordinate of a cell.
+    pub fn start(&self, index: usize) -> T {
+        self.starts[index]
+    }
+
+    /// Number of real coordinates a cell stands for. Only gap cells are
+    /// larger than one.
+    pub fn size(&self, index: usize) -> T {
+        self.sizes[index]
+    }
+}
+
+/// Compression of both axes of a set of 2D points. Compressed cells are
+/// addressed with `Point<i32>`, so they index straight into a [`Grid`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression<T = i32> {
+    pub x: CompressedAxis<T>,
+    pub y: CompressedAxis<T>,
+}
+
+impl<T: Coordinate> Compression<T> {
+    pub fn new<'a, I>(points: I, gaps: bool) -> Self
+    where
+        I: IntoIterator<Item = &'a Point<T>>,
+        T: 'a,
+    {
+        let (xs, ys): (Vec<_>, Vec<_>) = points.into_iter().map(|p| (p.x, p.y)).unzip();
+        Self {
+            x: CompressedAxis::new(xs, gaps),
+            y: CompressedAxis::new(ys, gaps),
+        }
+    }
+
+    pub fn padded(self) -> Self {
+        Self {
+            x: self.x.padded(),
+            y: self.y.padded(),
+        }
+    }
+
+    /// Width and height of the compressed grid.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.x.len(), self.y.len())
+    }
+
+    pub fn compress(&self, point: &Point<T>) -> Option<Point> {
+        Some(Point::new(
+            self.x.index(point.x)? as i32,
+            self.y.index(point.y)? as i32,
+        ))
+    }
+
+    /// The top-left real point of a compressed cell.
+    pub fn decompress(&self, cell: &Point) -> Point<T> {
+        Point::new(self.x.start(cell.x as usize), self.y.start(cell.y as usize))
+    }
+
+    /// Number of real points a compressed cell stands for.
+    pub fn cell_area(&self, cell: &Point) -> T {
+        self.x.size(cell.x as usize) * self.y.size(cell.y as usize)
+    }
+
+    /// The compressed cells covered by the straight segment from `a` to `b`.
+    /// Both ends must be compressible and share an axis.
+    pub fn line(&self, a: &Point<T>, b: &Point<T>) -> Vec<Point> {
+        let from = self.compress(a).expect("line start should be compressible");
+        let to = self.compress(b).expect("line end should be compressible");
+        assert!(
+            from.x == to.x || from.y == to.y,
+            "line should be axis-aligned"
+        );
+        let step = (to - from).signum();
+        let mut cells = vec![from];
+        let mut current = from;
+        while current != to {
+            current += step;
+            cells.push(current);
+        }
+        cells
+    }
+
+    /// A compressed grid filled with `item`.
+    pub fn grid<U: Clone>(&self, item: U) -> Grid<U> {
+        let (width, height) = self.dimensions();
+        Grid::filled_with(item, width, height)
+    }
+
+    /// Total real area of the compressed cells for which `f` returns `true`.
+    pub fn area<U, F>(&self, grid: &Grid<U>, mut f: F) -> T
+    where
+        F: FnMut(&Point, &U) -> bool,
+    {
+        grid.iter()
+            .filter(|(cell, item)| f(cell, item))
+            .fold(T::zero(), |area, (cell, _)| area + self.cell_area(&cell))
+    }
+}
+
+/// Compression of all three axes of a set of 3D points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression3D<T> {
+    pub x: CompressedAxis<T>,
+    pub y: CompressedAxis<T>,
+    pub z: CompressedAxis<T>,
+}
+
+impl<T: PrimInt> Compression3D<T> {
+    pub fn new<'a, I>(points: I, gaps: bool) -> Self
+    where
+        I: IntoIterator<Item = &'a Point3D<T>>,
+        T: 'a,
+    {
+        let points = points.into_iter().collect::<Vec<_>>();
+        Self {
+            x: CompressedAxis::new(points.iter().map(|p| p.x), gaps),
+            y: CompressedAxis::new(points.iter().map(|p| p.y), gaps),
+            z: CompressedAxis::new(points.iter().map(|p| p.z), gaps),
+        }
+    }
+
+    pub fn padded(self) -> Self {
+        Self {
+            x: self.x.padded(),
+            y: self.y.padded(),
+            z: self.z.padded(),
+        }
+    }
+
+    /// Width, depth and height of the compressed grid, matching [`Grid3D`].
+    pub fn dimensions(&self) -> (usize, usize, usize) {
+        (self.x.len(), self.y.len(), self.z.len())
+    }
+
+    pub fn compress(&self, point: &Point3D<T>) -> Option<Point3D<usize>> {
+        Some(Point3D::new(
+            self.x.index(point.x)?,
+            self.y.index(point.y)?,
+            self.z.index(point.z)?,
+        ))
+    }
+
+    pub fn decompress(&self, cell: &Point3D<usize>) -> Point3D<T> {
+        Point3D::new(
+            self.x.start(cell.x),
+            self.y.start(cell.y),
+            self.z.start(cell.z),
+        )
+    }
+
+    /// Number of real points a compressed cell stands for.
+    pub fn cell_volume(&self, cell: &Point3D<usize>) -> T {
+        self.x.size(cell.x) * self.y.size(cell.y) * self.z.size(cell.z)
+    }
+
+    pub fn grid<U: Clone>(&self, item: U) -> Grid3D<U> {
+        let (width, depth, height) = self.dimensions();
+        Grid3D::filled_with(item, width, depth, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        direction::Direction,
+        polygon::Polygon,
+        region::{flood_fill, Connectivity},
+    };
+
+    #[test]
+    fn test_axis() {
+        let axis = CompressedAxis::new([10, 3, 4, 10, 20], true);
+        let cells = (0..axis.len())
+            .map(|i| (axis.start(i), axis.size(i)))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, [(3, 1), (4, 1), (5, 5), (10, 1), (11, 9), (20, 1)]);
+        assert_eq!(axis.index(7), Some(2));
+        assert_eq!(axis.index(21), None);
+
+        let tight = CompressedAxis::new([10, 3, 4, 20], false);
+        assert_eq!(tight.len(), 4);
+        assert_eq!(tight.index(7), None);
+        assert_eq!(tight.index(10), Some(2));
+
+        let padded = tight.padded();
+        assert_eq!((padded.start(0), padded.start(5)), (2, 21));
+        assert_eq!(padded.index(10), Some(3));
+
+        let unsigned = CompressedAxis::new([0u32, 3], true).padded();
+        assert_eq!(unsigned.len(), 4);
+        assert_eq!((unsigned.start(0), unsigned.start(3)), (0, 4));
+        assert_eq!(unsigned.index(2), Some(1));
+    }
+
+    #[test]
+    fn test_dig_plan_area() {
+        // 2023 day 18 example, scaled up so the compression matters
+        let plan = "R6 D5 L2 D2 R2 D2 L5 U2 L1 U2 R2 U3 L2 U2";
+        let corners = plan
+            .split(' ')
+            .scan(Point::<i64>::new(0, 0), |point, step| {
+                let direction = step[..1].parse::<Direction>().unwrap();
+                let distance = step[1..].parse::<i64>().unwrap();
+                *point = point.move_n_in_direction(direction, distance * 1000);
+                Some(*point)
+            })
+            .collect::<Vec<_>>();
+
+        let compression = Compression::new(&corners, true).padded();
+        assert_eq!(compression.dimensions(), (11, 11));
+        let mut grid = compression.grid('.');
+        for (a, b) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+            for cell in compression.line(a, b) {
+                grid[cell] = '#';
+            }
+        }
+        let outside = flood_fill(&grid, [Point::new(0, 0)], Connectivity::Four, |_, c| {
+            *c == '.'
+        });
+        for cell in outside {
+            grid[cell] = ' ';
+        }
+        let area = compression.area(&grid, |_, c| *c != ' ');
+        assert_eq!(area, Polygon::new(corners).lattice_points());
+
+        let cell = compression.compress(&Point::new(6000, 2500)).unwrap();
+        assert_eq!(grid[cell], '#');
+        assert_eq!(compression.decompress(&cell), Point::new(6000, 2001));
+        assert_eq!(compression.cell_area(&cell), 2999);
+    }
+
+    #[test]
+    fn test_3d() {
+        let points = [Point3D::new(0, 0, 0), Point3D::new(10, 1, 5)];
+        let compression = Compression3D::new(&points, true);
+        assert_eq!(compression.dimensions(), (3, 2, 3));
+        let cell = compression.compress(&Point3D::new(4, 1, 2)).unwrap();
+        assert_eq!(cell, Point3D::new(1, 1, 1));
+        assert_eq!(compression.decompress(&cell), Point3D::new(1, 1, 1));
+        assert_eq!(compression.cell_volume(&cell), 9 * 4);
+        assert_eq!(compression.grid(false).len(), 18);
+    }
+}
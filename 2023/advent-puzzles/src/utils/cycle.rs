@@ -0,0 +1,242 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+use num::PrimInt;
+
+/// Shape of a sequence `x0, f(x0), f(f(x0)), ...` over a finite state space:
+/// the first `prefix_len` states never come back, after which the sequence
+/// repeats every `period` states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub prefix_len: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest index whose state equals the state at index `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix_len {
+            n
+        } else {
+            self.prefix_len + (n - self.prefix_len) % self.period
+        }
+    }
+}
+
+impl From<Cycle> for (usize, usize) {
+    fn from(cycle: Cycle) -> Self {
+        (cycle.prefix_len, cycle.period)
+    }
+}
+
+/// Brent's algorithm. Only ever keeps two states, but needs `step` to be
+/// deterministic and states to be cheap to compare.
+pub fn brent<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle {
+        prefix_len: prefix_len(start, &mut step, period),
+        period,
+    }
+}
+
+/// Floyd's tortoise and hare.
+pub fn floyd<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle {
+        prefix_len: prefix_len(start, &mut step, period),
+        period,
+    }
+}
+
+/// Walks two pointers `period` apart from the start until they meet.
+fn prefix_len<S, F>(start: S, step: &mut F, period: usize) -> usize
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = start.clone();
+    let mut hare = (0..period).fold(start, |state, _| step(&state));
+    let mut prefix_len = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix_len += 1;
+    }
+    prefix_len
+}
+
+/// Every state up to and including the first repeat, as found by
+/// [`detect`] or [`detect_by_key`].
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    states: Vec<S>,
+    cycle: Cycle,
+}
+
+impl<S> History<S> {
+    pub fn cycle(&self) -> Cycle {
+        self.cycle
+    }
+
+    /// The states from the start through the first state seen twice, so
+    /// there are `prefix_len + period + 1` of them.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// The state after `n` steps, without simulating them.
+    pub fn nth_state(&self, n: usize) -> &S {
+        &self.states[self.cycle.reduce(n)]
+    }
+
+    /// A measurement of the state after `n` steps.
+    pub fn extrapolate<T, F: Fn(&S) -> T>(&self, n: usize, metric: F) -> T {
+        metric(self.nth_state(n))
+    }
+
+    /// A measurement that grows by the same amount every period, such as a
+    /// tower height when only the top of the tower is part of the key.
+    pub fn extrapolate_linear<T, F>(&self, n: usize, metric: F) -> T
+    where
+        T: PrimInt,
+        F: Fn(&S) -> T,
+    {
+        let Cycle { prefix_len, period } = self.cycle;
+        if n < self.states.len() {
+            return metric(&self.states[n]);
+        }
+        let drift = metric(&self.states[prefix_len + period]) - metric(&self.states[prefix_len]);
+        let periods = T::from((n - prefix_len) / period).expect("period count should fit");
+        metric(self.nth_state(n)) + drift * periods
+    }
+}
+
+/// Steps until a state repeats, remembering each one in a hash map. Every
+/// state is cloned as its own key, so prefer [`detect_by_key`] with a
+/// fingerprint for large states such as grids.
+pub fn detect<S, F>(start: S, step: F) -> History<S>
+where
+    S: Clone + Hash + Eq,
+    F: FnMut(&S) -> S,
+{
+    detect_by_key(start, step, S::clone)
+}
+
+/// Like [`detect`], but compares states by `key`: a cheaper fingerprint, or
+/// a projection that leaves out values that only drift, like a height.
+pub fn detect_by_key<S, K, F, G>(start: S, mut step: F, mut key: G) -> History<S>
+where
+    K: Hash + Eq,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let mut seen = HashMap::new();
+    let mut states = vec![start];
+    loop {
+        let current = states.last().unwrap();
+        let index = states.len() - 1;
+        match seen.entry(key(current)) {
+            Entry::Occupied(first) => {
+                let first = *first.get();
+                let cycle = Cycle {
+                    prefix_len: first,
+                    period: index - first,
+                };
+                return History { states, cycle };
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(index);
+            }
+        }
+        let next = step(current);
+        states.push(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        let history = detect(3, step);
+        let cycle = history.cycle();
+        assert_eq!(brent(3, step), cycle);
+        assert_eq!(floyd(3, step), cycle);
+        assert_eq!(history.states().len(), cycle.prefix_len + cycle.period + 1);
+
+        let mut x = 3;
+        for n in 0..1000 {
+            assert_eq!(*history.nth_state(n), x, "state {}", n);
+            x = step(&x);
+        }
+        let pure_cycle = detect(0u8, |x| x.wrapping_add(1)).cycle();
+        assert_eq!(<(usize, usize)>::from(pure_cycle), (0, 256));
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // A repeating pattern of three phases, each adding to a running total
+        let spin = |&(phase, total): &(usize, u64)| ((phase + 1) % 3, total + [1, 2, 4][phase]);
+        let history = detect_by_key((0, 0), spin, |&(phase, _)| phase);
+        assert_eq!(
+            history.cycle(),
+            Cycle {
+                prefix_len: 0,
+                period: 3
+            }
+        );
+
+        let mut state = (0, 0);
+        for n in 0..100 {
+            assert_eq!(history.extrapolate(n, |&(phase, _)| phase), state.0);
+            assert_eq!(history.extrapolate_linear(n, |&(_, total)| total), state.1);
+            state = spin(&state);
+        }
+        assert_eq!(
+            history.extrapolate_linear(1_000_000_000_000, |&(_, total)| total),
+            1_000_000_000_000 / 3 * 7 + 1
+        );
+    }
+}
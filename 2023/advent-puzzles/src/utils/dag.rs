@@ -0,0 +1,224 @@
+use thiserror::Error;
+
+use super::graph::{Graph, NodeId};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DagError {
+    #[error("graph is undirected")]
+    Undirected,
+    /// The names along one cycle, starting and ending at the same node.
+    #[error("graph has a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
+
+/// A directed acyclic [`Graph`] together with a topological order of its
+/// nodes. Everything here runs in `O(V + E)` without recursion.
+#[derive(Debug, Clone)]
+pub struct Dag<'a> {
+    graph: &'a Graph,
+    order: Vec<NodeId>,
+    /// Index of every node in `order`.
+    position: Vec<usize>,
+}
+
+impl<'a> Dag<'a> {
+    /// Sorts `graph` topologically with Kahn's algorithm, or reports a cycle
+    /// if there is one.
+    pub fn new(graph: &'a Graph) -> Result<Self, DagError> {
+        if !graph.is_directed() {
+            return Err(DagError::Undirected);
+        }
+        let mut in_degree = vec![0; graph.len()];
+        for &(_, to) in graph.edges() {
+            in_degree[to as usize] += 1;
+        }
+        let mut order = graph
+            .nodes()
+            .filter(|&id| in_degree[id as usize] == 0)
+            .collect::<Vec<_>>();
+        let mut next = 0;
+        while let Some(&node) = order.get(next) {
+            next += 1;
+            for &neighbour in graph.neighbours(node) {
+                in_degree[neighbour as usize] -= 1;
+                if in_degree[neighbour as usize] == 0 {
+                    order.push(neighbour);
+                }
+            }
+        }
+        if order.len() < graph.len() {
+            return Err(DagError::Cycle(find_cycle(graph, &in_degree)));
+        }
+
+        let mut position = vec![0; graph.len()];
+        for (index, &id) in order.iter().enumerate() {
+            position[id as usize] = index;
+        }
+        Ok(Self {
+            graph,
+            order,
+            position,
+        })
+    }
+
+    pub fn graph(&self) -> &'a Graph {
+        self.graph
+    }
+
+    /// Every node, each one before all the nodes it has edges to.
+    pub fn order(&self) -> &[NodeId] {
+        &self.order
+    }
+
+    /// Index of `id` in [`Dag::order`].
+    pub fn position(&self, id: NodeId) -> usize {
+        self.position[id as usize]
+    }
+
+    /// Number of distinct paths from `from` to every node.
+    pub fn count_paths_from(&self, from: NodeId) -> Vec<u64> {
+        let mut counts = vec![0; self.graph.len()];
+        counts[from as usize] = 1;
+        for &node in &self.order[self.position(from)..] {
+            let count = counts[node as usize];
+            if count == 0 {
+                continue;
+            }
+            for &neighbour in self.graph.neighbours(node) {
+                counts[neighbour as usize] += count;
+            }
+        }
+        counts
+    }
+
+    /// Number of distinct paths from `from` to `to`. A node has one path to
+    /// itself.
+    pub fn count_paths(&self, from: NodeId, to: NodeId) -> u64 {
+        if self.position(to) < self.position(from) {
+            return 0;
+        }
+        self.count_paths_from(from)[to as usize]
+    }
+
+    /// Number of paths from `from` to `to` that pass through every waypoint,
+    /// in any order. A path can only meet the waypoints in topological
+    /// order, so this is the product of the path counts between consecutive
+    /// waypoints in that order.
+    pub fn count_paths_via(&self, from: NodeId, to: NodeId, waypoints: &[NodeId]) -> u64 {
+        let mut stops = waypoints.to_vec();
+        stops.sort_unstable_by_key(|&id| self.position(id));
+        stops.dedup();
+        stops.insert(0, from);
+        stops.push(to);
+        stops
+            .windows(2)
+            .map(|pair| self.count_paths(pair[0], pair[1]))
+            .product()
+    }
+}
+
+/// Follows edges between nodes Kahn's algorithm could not order until one
+/// repeats. Each of those nodes has an incoming edge from another of them,
+/// so walking backwards never gets stuck.
+fn find_cycle(graph: &Graph, in_degree: &[usize]) -> Vec<String> {
+    let mut predecessor = vec![None; graph.len()];
+    for &(from, to) in graph.edges() {
+        if in_degree[from as usize] > 0 && in_degree[to as usize] > 0 {
+            predecessor[to as usize] = Some(from);
+        }
+    }
+    let start = graph
+        .nodes()
+        .find(|&id| in_degree[id as usize] > 0)
+        .expect("an unordered node");
+
+    let mut visited = vec![false; graph.len()];
+    let mut node = start;
+    while !visited[node as usize] {
+        visited[node as usize] = true;
+        node = predecessor[node as usize].expect("every unordered node has a predecessor");
+    }
+
+    // `node` is on the cycle; walk it once more to collect it in edge order
+    let mut cycle = vec![node];
+    let mut current = predecessor[node as usize].unwrap();
+    while current != node {
+        cycle.push(current);
+        current = predecessor[current as usize].unwrap();
+    }
+    cycle.push(node);
+    cycle.reverse();
+    cycle
+        .into_iter()
+        .map(|id| graph.name(id).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025 day 11, second example
+    const SERVER_RACK: &str = "svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out";
+
+    #[test]
+    fn test_order() {
+        let graph = Graph::parse_adjacency(SERVER_RACK, ":", true).unwrap();
+        let dag = Dag::new(&graph).unwrap();
+        assert_eq!(dag.order().len(), graph.len());
+        for &(from, to) in graph.edges() {
+            assert!(dag.position(from) < dag.position(to));
+        }
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let graph = Graph::parse_adjacency(SERVER_RACK, ":", true).unwrap();
+        let dag = Dag::new(&graph).unwrap();
+        let id = |name| graph.id(name).unwrap();
+
+        assert_eq!(dag.count_paths(id("svr"), id("out")), 8);
+        assert_eq!(dag.count_paths(id("ccc"), id("fff")), 2);
+        assert_eq!(dag.count_paths(id("out"), id("svr")), 0);
+        assert_eq!(dag.count_paths(id("hub"), id("hub")), 1);
+        assert_eq!(
+            dag.count_paths_via(id("svr"), id("out"), &[id("dac"), id("fft")]),
+            2
+        );
+        assert_eq!(
+            dag.count_paths_via(id("svr"), id("out"), &[id("fft"), id("dac")]),
+            2,
+            "waypoint order does not matter"
+        );
+        assert_eq!(
+            dag.count_paths_via(id("svr"), id("out"), &[id("fft"), id("tty")]),
+            0
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let graph = Graph::parse_adjacency("a: b\nb: c\nc: d b\nd: e", ":", true).unwrap();
+        let error = Dag::new(&graph).unwrap_err();
+        assert_eq!(
+            error,
+            DagError::Cycle(vec!["b".into(), "c".into(), "b".into()])
+        );
+        assert_eq!(error.to_string(), "graph has a cycle: b -> c -> b");
+
+        let undirected = Graph::parse_adjacency("a: b", ":", false).unwrap();
+        assert_eq!(Dag::new(&undirected).unwrap_err(), DagError::Undirected);
+    }
+}
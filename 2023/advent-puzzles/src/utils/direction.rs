@@ -1,6 +1,17 @@
 use std::{fmt::Display, str::FromStr};
 
 use strum_macros::EnumIter;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseDirectionError {
+    #[error("invalid direction: {0:?}")]
+    InvalidDirection(String),
+    #[error("invalid turn: {0:?}")]
+    InvalidTurn(String),
+    #[error("{0} is not a cardinal direction")]
+    NotCardinal(Direction8),
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, EnumIter)]
 pub enum Direction {
@@ -37,25 +48,60 @@ impl Direction {
             Direction::West => Direction::North,
         }
     }
+
+    pub fn turn(&self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+            Turn::Straight => *self,
+            Turn::Back => self.opposite(),
+        }
+    }
+
+    /// The `(dx, dy)` of one step, with y growing downwards (North is `(0, -1)`).
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
 }
 
-impl From<char> for Direction {
-    fn from(c: char) -> Self {
+/// Accepts arrows (`^>v<`), `UDLR` and compass letters (`NESW`), in either case.
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            '^' | 'U' => Self::North,
-            '>' | 'R' => Self::East,
-            'v' | 'D' => Self::South,
-            '<' | 'L' => Self::West,
-            _ => panic!("Invalid direction: {}", c),
+            '^' | 'U' | 'u' | 'N' | 'n' => Ok(Self::North),
+            '>' | 'R' | 'r' | 'E' | 'e' => Ok(Self::East),
+            'v' | 'V' | 'D' | 'd' | 'S' | 's' => Ok(Self::South),
+            '<' | 'L' | 'l' | 'W' | 'w' => Ok(Self::West),
+            _ => Err(ParseDirectionError::InvalidDirection(c.to_string())),
         }
     }
 }
 
+/// Accepts every single character [`TryFrom<char>`] does, plus the words
+/// `north`/`up`, `east`/`right`, `south`/`down` and `west`/`left` in any case.
 impl FromStr for Direction {
-    type Err = ();
+    type Err = ParseDirectionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self::from(s.chars().next().unwrap()))
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Self::try_from(c);
+        }
+
+        match s.to_ascii_lowercase().as_str() {
+            "north" | "up" => Ok(Self::North),
+            "east" | "right" => Ok(Self::East),
+            "south" | "down" => Ok(Self::South),
+            "west" | "left" => Ok(Self::West),
+            _ => Err(ParseDirectionError::InvalidDirection(s.to_string())),
+        }
     }
 }
 
@@ -76,3 +122,251 @@ impl Display for Direction {
         write!(f, "{}", c)
     }
 }
+
+/// A turn relative to the current heading.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, EnumIter)]
+pub enum Turn {
+    Left,
+    Right,
+    Straight,
+    Back,
+}
+
+impl Turn {
+    /// The turn that changes heading `from` into heading `to`.
+    pub fn between(from: Direction, to: Direction) -> Self {
+        if from == to {
+            Turn::Straight
+        } else if from.turn_left() == to {
+            Turn::Left
+        } else if from.turn_right() == to {
+            Turn::Right
+        } else {
+            Turn::Back
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Turn::Left => Turn::Right,
+            Turn::Right => Turn::Left,
+            Turn::Straight => Turn::Back,
+            Turn::Back => Turn::Straight,
+        }
+    }
+}
+
+/// Accepts `L`, `R`, `S`/`F` (straight/forward) and `B`, in either case.
+impl TryFrom<char> for Turn {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c.to_ascii_uppercase() {
+            'L' => Ok(Turn::Left),
+            'R' => Ok(Turn::Right),
+            'S' | 'F' => Ok(Turn::Straight),
+            'B' => Ok(Turn::Back),
+            _ => Err(ParseDirectionError::InvalidTurn(c.to_string())),
+        }
+    }
+}
+
+impl FromStr for Turn {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Self::try_from(c);
+        }
+
+        match s.to_ascii_lowercase().as_str() {
+            "left" => Ok(Turn::Left),
+            "right" => Ok(Turn::Right),
+            "straight" | "forward" => Ok(Turn::Straight),
+            "back" | "around" => Ok(Turn::Back),
+            _ => Err(ParseDirectionError::InvalidTurn(s.to_string())),
+        }
+    }
+}
+
+/// The eight compass directions, clockwise from North.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, EnumIter)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    const CLOCKWISE: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    fn rotate(&self, steps: usize) -> Self {
+        Self::CLOCKWISE[(*self as usize + steps) % 8]
+    }
+
+    pub fn turn_right_45(&self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn turn_left_45(&self) -> Self {
+        self.rotate(7)
+    }
+
+    pub fn turn_right(&self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(&self) -> Self {
+        self.rotate(6)
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        *self as usize % 2 == 1
+    }
+
+    /// The `(dx, dy)` of one step, with y growing downwards (North is `(0, -1)`).
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West,
+        }
+    }
+}
+
+impl TryFrom<Direction8> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(direction: Direction8) -> Result<Self, Self::Error> {
+        match direction {
+            Direction8::North => Ok(Direction::North),
+            Direction8::East => Ok(Direction::East),
+            Direction8::South => Ok(Direction::South),
+            Direction8::West => Ok(Direction::West),
+            diagonal => Err(ParseDirectionError::NotCardinal(diagonal)),
+        }
+    }
+}
+
+/// Accepts compass abbreviations (`N`, `NE`, `sw`, ...), the full names with
+/// or without a separator (`north-east`, `NorthEast`, `north east`) and
+/// anything [`Direction`] parses.
+impl FromStr for Direction8 {
+    type Err = ParseDirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        match normalized.as_str() {
+            "ne" | "northeast" => Ok(Direction8::NorthEast),
+            "se" | "southeast" => Ok(Direction8::SouthEast),
+            "sw" | "southwest" => Ok(Direction8::SouthWest),
+            "nw" | "northwest" => Ok(Direction8::NorthWest),
+            _ => s
+                .parse::<Direction>()
+                .map(Direction8::from)
+                .map_err(|_| ParseDirectionError::InvalidDirection(s.to_string())),
+        }
+    }
+}
+
+impl Display for Direction8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Direction8::North => "N",
+            Direction8::NorthEast => "NE",
+            Direction8::East => "E",
+            Direction8::SouthEast => "SE",
+            Direction8::South => "S",
+            Direction8::SouthWest => "SW",
+            Direction8::West => "W",
+            Direction8::NorthWest => "NW",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_parse_direction() {
+        assert_eq!(Direction::try_from('^'), Ok(Direction::North));
+        assert_eq!(Direction::try_from('L'), Ok(Direction::West));
+        assert_eq!(Direction::try_from('s'), Ok(Direction::South));
+        assert_eq!("East".parse(), Ok(Direction::East));
+        assert_eq!("down".parse(), Ok(Direction::South));
+        assert!(Direction::try_from('x').is_err());
+        assert!("".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Direction::North.turn(Turn::Left), Direction::West);
+        assert_eq!(Direction::North.turn(Turn::Back), Direction::South);
+        for from in Direction::iter() {
+            for to in Direction::iter() {
+                assert_eq!(from.turn(Turn::between(from, to)), to);
+            }
+        }
+        assert_eq!("F".parse(), Ok(Turn::Straight));
+    }
+
+    #[test]
+    fn test_direction8() {
+        assert_eq!(Direction8::North.turn_right_45(), Direction8::NorthEast);
+        assert_eq!(Direction8::North.turn_left_45(), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthWest.opposite(), Direction8::NorthEast);
+        assert!(Direction8::SouthEast.is_diagonal());
+        assert_eq!(Direction8::from(Direction::West).delta(), (-1, 0));
+        assert_eq!(
+            Direction::try_from(Direction8::NorthEast),
+            Err(ParseDirectionError::NotCardinal(Direction8::NorthEast))
+        );
+        assert_eq!("north-east".parse(), Ok(Direction8::NorthEast));
+        assert_eq!("SW".parse(), Ok(Direction8::SouthWest));
+        assert_eq!("W".parse(), Ok(Direction8::West));
+        assert_eq!(Direction8::iter().count(), 8);
+    }
+}
@@ -0,0 +1,114 @@
+/// Memoization table for DP over bounded integer indices, backed by a flat
+/// `Vec<Option<V>>` instead of a hash map. Create (or [`reset`](Self::reset))
+/// one per top-level call and thread it through the recursion.
+#[derive(Debug, Clone)]
+pub struct DpTable<V, const D: usize> {
+    dims: [usize; D],
+    cells: Vec<Option<V>>,
+}
+
+impl<V, const D: usize> DpTable<V, D> {
+    /// Creates a table where index `i` ranges over `0..dims[i]`.
+    pub fn new(dims: [usize; D]) -> Self {
+        let size = dims.iter().product();
+        let mut cells = Vec::with_capacity(size);
+        cells.resize_with(size, || None);
+        Self { dims, cells }
+    }
+
+    pub fn dims(&self) -> [usize; D] {
+        self.dims
+    }
+
+    fn offset(&self, index: [usize; D]) -> Option<usize> {
+        let mut offset = 0;
+        for (i, dim) in index.into_iter().zip(self.dims) {
+            if i >= dim {
+                return None;
+            }
+            offset = offset * dim + i;
+        }
+        Some(offset)
+    }
+
+    /// Returns the stored value, or `None` if it was not computed yet or the
+    /// index is out of bounds.
+    pub fn get(&self, index: [usize; D]) -> Option<&V> {
+        self.cells[self.offset(index)?].as_ref()
+    }
+
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn insert(&mut self, index: [usize; D], value: V) {
+        let offset = self
+            .offset(index)
+            .unwrap_or_else(|| panic!("index {:?} out of bounds {:?}", index, self.dims));
+        self.cells[offset] = Some(value);
+    }
+
+    pub fn contains(&self, index: [usize; D]) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Forgets all stored values, keeping the allocation.
+    pub fn reset(&mut self) {
+        self.cells.iter_mut().for_each(|cell| *cell = None);
+    }
+
+    /// Resizes to new bounds and forgets all stored values.
+    pub fn reset_with_dims(&mut self, dims: [usize; D]) {
+        self.dims = dims;
+        self.cells.clear();
+        self.cells.resize_with(dims.iter().product(), || None);
+    }
+
+    /// Number of stored values.
+    pub fn len(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<V: Clone, const D: usize> DpTable<V, D> {
+    pub fn get_cloned(&self, index: [usize; D]) -> Option<V> {
+        self.get(index).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut table = DpTable::new([3, 4]);
+        assert_eq!(table.get([1, 2]), None);
+        table.insert([1, 2], 42);
+        table.insert([2, 3], 7);
+        assert_eq!(table.get([1, 2]), Some(&42));
+        assert_eq!(table.get([2, 3]), Some(&7));
+        assert_eq!(table.get([2, 1]), None);
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let table = DpTable::<u8, 2>::new([3, 4]);
+        assert_eq!(table.get([3, 0]), None);
+        assert_eq!(table.get([0, 4]), None);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut table = DpTable::new([2]);
+        table.insert([1], "a");
+        table.reset();
+        assert!(table.is_empty());
+        table.reset_with_dims([5]);
+        table.insert([4], "b");
+        assert_eq!(table.get([4]), Some(&"b"));
+    }
+}
@@ -0,0 +1,374 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Union-find over the nodes `0..len`, with union by size and path halving.
+/// The number of components and the size of each one are kept up to date on
+/// every union.
+#[derive(Debug, Clone)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl Dsu {
+    /// `len` nodes, each in a component of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            count: len,
+        }
+    }
+
+    /// Adds a node in a component of its own and returns its index.
+    pub fn add(&mut self) -> usize {
+        let index = self.parent.len();
+        self.parent.push(index);
+        self.size.push(1);
+        self.count += 1;
+        index
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// The representative of the component containing `x`.
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merges the components of `a` and `b`. Returns `false` if they were
+    /// already the same component.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the component containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.count
+    }
+
+    /// The size of every component, in no particular order.
+    pub fn component_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        roots(&self.parent).map(|root| self.size[root])
+    }
+
+    /// The `k` largest component sizes, largest first.
+    pub fn largest(&self, k: usize) -> Vec<usize> {
+        largest(self.component_sizes(), k)
+    }
+
+    /// The nodes of every component, grouped in order of their smallest node.
+    pub fn components(&mut self) -> impl Iterator<Item = Vec<usize>> {
+        let mut slots = vec![None; self.len()];
+        let mut components = Vec::<Vec<usize>>::with_capacity(self.count);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let slot = *slots[root].get_or_insert_with(|| {
+                components.push(Vec::with_capacity(self.size[root]));
+                components.len() - 1
+            });
+            components[slot].push(x);
+        }
+        components.into_iter()
+    }
+}
+
+/// Union-find over arbitrary keys such as `Point`s or `String`s. Keys are
+/// added the first time they are seen.
+#[derive(Debug, Clone)]
+pub struct HashDsu<K> {
+    index: HashMap<K, usize>,
+    keys: Vec<K>,
+    dsu: Dsu,
+}
+
+impl<K> Default for HashDsu<K> {
+    fn default() -> Self {
+        Self {
+            index: HashMap::new(),
+            keys: Vec::new(),
+            dsu: Dsu::new(0),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> HashDsu<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `key` in a component of its own, unless it is already known.
+    /// Returns its index.
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&index) = self.index.get(&key) {
+            return index;
+        }
+        let index = self.dsu.add();
+        self.index.insert(key.clone(), index);
+        self.keys.push(key);
+        index
+    }
+
+    pub fn index(&self, key: &K) -> Option<usize> {
+        self.index.get(key).copied()
+    }
+
+    pub fn key(&self, index: usize) -> &K {
+        &self.keys[index]
+    }
+
+    /// Number of keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The representative key of the component containing `key`.
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let index = self.index(key)?;
+        Some(&self.keys[self.dsu.find(index)])
+    }
+
+    /// Merges the components of `a` and `b`, adding either if it is new.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.dsu.union(a, b)
+    }
+
+    /// Whether both keys are known and in the same component.
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.index(a), self.index(b)) {
+            (Some(a), Some(b)) => self.dsu.same(a, b),
+            _ => false,
+        }
+    }
+
+    /// Size of the component containing `key`, if it is known.
+    pub fn size(&mut self, key: &K) -> Option<usize> {
+        let index = self.index(key)?;
+        Some(self.dsu.size(index))
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.dsu.component_count()
+    }
+
+    pub fn component_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.dsu.component_sizes()
+    }
+
+    pub fn largest(&self, k: usize) -> Vec<usize> {
+        self.dsu.largest(k)
+    }
+
+    /// The keys of every component, grouped in insertion order.
+    pub fn components(&mut self) -> impl Iterator<Item = Vec<&K>> {
+        let keys = &self.keys;
+        self.dsu
+            .components()
+            .map(move |component| component.into_iter().map(|i| &keys[i]).collect())
+    }
+}
+
+/// A point in a [`RollbackDsu`]'s history to return to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot(usize);
+
+/// Union-find that can undo unions, for offline algorithms that explore a
+/// branch and back out of it. Uses union by size only, so `find` does not
+/// need `&mut self` and stays logarithmic.
+#[derive(Debug, Clone)]
+pub struct RollbackDsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+    /// The roots attached by each successful union, in order.
+    history: Vec<usize>,
+}
+
+impl RollbackDsu {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            count: len,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.count -= 1;
+        self.history.push(b);
+        true
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.find(x)]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn component_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        roots(&self.parent).map(|root| self.size[root])
+    }
+
+    pub fn largest(&self, k: usize) -> Vec<usize> {
+        largest(self.component_sizes(), k)
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot(self.history.len())
+    }
+
+    /// Undoes every union made since `snapshot` was taken.
+    pub fn rollback(&mut self, snapshot: Snapshot) {
+        while self.history.len() > snapshot.0 {
+            let child = self.history.pop().unwrap();
+            let root = self.parent[child];
+            self.size[root] -= self.size[child];
+            self.parent[child] = child;
+            self.count += 1;
+        }
+    }
+}
+
+fn roots(parent: &[usize]) -> impl Iterator<Item = usize> + '_ {
+    (0..parent.len()).filter(|&x| parent[x] == x)
+}
+
+fn largest<I: Iterator<Item = usize>>(sizes: I, k: usize) -> Vec<usize> {
+    let mut sizes = sizes.collect::<Vec<_>>();
+    if k < sizes.len() {
+        sizes.select_nth_unstable_by(k, |a, b| b.cmp(a));
+        sizes.truncate(k);
+    }
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::point::Point;
+
+    #[test]
+    fn test_dsu() {
+        let mut dsu = Dsu::new(8);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+        assert!(dsu.union(5, 6));
+
+        assert_eq!(dsu.component_count(), 4);
+        assert!(dsu.same(0, 3) && !dsu.same(0, 5));
+        assert_eq!(dsu.size(2), 4);
+        assert_eq!(dsu.largest(2), [4, 2]);
+        assert_eq!(dsu.largest(10), [4, 2, 1, 1]);
+        assert_eq!(
+            dsu.components().collect::<Vec<_>>(),
+            [vec![0, 1, 2, 3], vec![4], vec![5, 6], vec![7]]
+        );
+
+        let added = dsu.add();
+        assert_eq!((added, dsu.component_count()), (8, 5));
+    }
+
+    #[test]
+    fn test_hash_dsu() {
+        let mut dsu = HashDsu::new();
+        dsu.union(Point::new(0, 0), Point::new(0, 1));
+        dsu.union(Point::new(5, 5), Point::new(0, 1));
+        dsu.insert(Point::new(9, 9));
+
+        assert_eq!(dsu.len(), 4);
+        assert_eq!(dsu.component_count(), 2);
+        assert!(dsu.same(&Point::new(0, 0), &Point::new(5, 5)));
+        assert!(!dsu.same(&Point::new(0, 0), &Point::new(7, 7)));
+        assert_eq!(dsu.size(&Point::new(5, 5)), Some(3));
+        assert_eq!(dsu.size(&Point::new(7, 7)), None);
+        assert_eq!(
+            dsu.components().map(|c| c.len()).collect::<Vec<_>>(),
+            [3, 1]
+        );
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut dsu = RollbackDsu::new(5);
+        dsu.union(0, 1);
+        let snapshot = dsu.snapshot();
+        dsu.union(1, 2);
+        dsu.union(3, 4);
+        dsu.union(0, 4);
+        assert_eq!((dsu.component_count(), dsu.size(3)), (1, 5));
+
+        dsu.rollback(snapshot);
+        assert_eq!(dsu.component_count(), 4);
+        assert!(dsu.same(0, 1) && !dsu.same(1, 2) && !dsu.same(3, 4));
+        assert_eq!(dsu.largest(5), [2, 1, 1, 1]);
+    }
+}
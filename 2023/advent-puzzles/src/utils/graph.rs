@@ -0,0 +1,610 @@
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use petgraph::EdgeType;
+use thiserror::Error;
+
+/// Compact node handle into a [`Graph`]. Nodes are numbered `0..len` in the
+/// order their names were first seen.
+pub type NodeId = u32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseGraphError {
+    #[error("missing {separator:?} in line {line:?}")]
+    MissingSeparator { line: String, separator: String },
+}
+
+/// Graph over named nodes. Names are interned once, so algorithms work on
+/// [`NodeId`]s and names are only looked up at the edges of a solution.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    directed: bool,
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    adjacency: Vec<Vec<NodeId>>,
+    /// Every edge once, in insertion order.
+    edges: Vec<(NodeId, NodeId)>,
+}
+
+impl Graph {
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn undirected() -> Self {
+        Self::default()
+    }
+
+    /// Parses lines of a node, `separator`, then its neighbours. Neighbours
+    /// are split on whitespace, commas and brackets, so `a: b c`,
+    /// `a -> b, c` and `a = (b, c)` all work. Blank lines are skipped.
+    pub fn parse_adjacency(
+        input: &str,
+        separator: &str,
+        directed: bool,
+    ) -> Result<Self, ParseGraphError> {
+        let mut graph = if directed {
+            Self::directed()
+        } else {
+            Self::undirected()
+        };
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (head, tail) = split_line(line, separator)?;
+            let head = graph.intern(head);
+            for neighbour in tail
+                .split(|c: char| c.is_whitespace() || ",()[]".contains(c))
+                .filter(|name| !name.is_empty())
+            {
+                let neighbour = graph.intern(neighbour);
+                graph.add_edge(head, neighbour);
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Parses one edge per line, such as `a-b` with a `"-"` separator.
+    pub fn parse_edges(
+        input: &str,
+        separator: &str,
+        directed: bool,
+    ) -> Result<Self, ParseGraphError> {
+        let mut graph = if directed {
+            Self::directed()
+        } else {
+            Self::undirected()
+        };
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (a, b) = split_line(line, separator)?;
+            graph.add_named_edge(a, b);
+        }
+        Ok(graph)
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// The id of `name`, adding it as a new node if it is not known yet.
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as NodeId;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.adjacency.push(Vec::new());
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id as usize]
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        0..self.len() as NodeId
+    }
+
+    /// Adds an edge from `a` to `b`, and from `b` to `a` when undirected.
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId) {
+        self.adjacency[a as usize].push(b);
+        if !self.directed && a != b {
+            self.adjacency[b as usize].push(a);
+        }
+        self.edges.push((a, b));
+    }
+
+    pub fn add_named_edge(&mut self, a: &str, b: &str) -> (NodeId, NodeId) {
+        let (a, b) = (self.intern(a), self.intern(b));
+        self.add_edge(a, b);
+        (a, b)
+    }
+
+    /// The nodes reachable over one edge, in insertion order.
+    pub fn neighbours(&self, id: NodeId) -> &[NodeId] {
+        &self.adjacency[id as usize]
+    }
+
+    /// Every edge once, as added. Undirected edges are not mirrored here.
+    pub fn edges(&self) -> &[(NodeId, NodeId)] {
+        &self.edges
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Flattens the adjacency lists for faster traversal.
+    pub fn to_csr(&self) -> Csr {
+        let mut offsets = Vec::with_capacity(self.len() + 1);
+        let mut targets = Vec::with_capacity(self.adjacency.iter().map(Vec::len).sum());
+        offsets.push(0);
+        for neighbours in &self.adjacency {
+            targets.extend_from_slice(neighbours);
+            offsets.push(targets.len() as u32);
+        }
+        Csr { offsets, targets }
+    }
+
+    /// A petgraph copy weighted by node name. Node indices equal the
+    /// [`NodeId`]s. Pick `Ty` to match [`Graph::is_directed`].
+    pub fn to_petgraph<Ty: EdgeType>(&self) -> petgraph::Graph<&str, (), Ty> {
+        let mut graph = petgraph::Graph::with_capacity(self.len(), self.edge_count());
+        for name in &self.names {
+            graph.add_node(name.as_str());
+        }
+        graph.extend_with_edges(&self.edges);
+        graph
+    }
+}
+
+fn split_line<'a>(line: &'a str, separator: &str) -> Result<(&'a str, &'a str), ParseGraphError> {
+    let (head, tail) =
+        line.split_once(separator)
+            .ok_or_else(|| ParseGraphError::MissingSeparator {
+                line: line.to_string(),
+                separator: separator.to_string(),
+            })?;
+    Ok((head.trim(), tail.trim()))
+}
+
+/// Compressed sparse row adjacency: the neighbours of every node packed into
+/// one array, sliced by offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Csr {
+    offsets: Vec<u32>,
+    targets: Vec<NodeId>,
+}
+
+impl Csr {
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn neighbours(&self, id: NodeId) -> &[NodeId] {
+        let id = id as usize;
+        &self.targets[self.offsets[id] as usize..self.offsets[id + 1] as usize]
+    }
+
+    pub fn degree(&self, id: NodeId) -> usize {
+        self.neighbours(id).len()
+    }
+}
+
+/// A partition of a graph's nodes and the total weight of the edges between
+/// the two sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    pub weight: u64,
+    /// The nodes on one side of the cut, sorted.
+    pub side: Vec<NodeId>,
+    node_count: usize,
+}
+
+impl MinCut {
+    fn new(weight: u64, mut side: Vec<NodeId>, node_count: usize) -> Self {
+        side.sort_unstable();
+        Self {
+            weight,
+            side,
+            node_count,
+        }
+    }
+
+    /// Whether `id` is in [`MinCut::side`].
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.side.binary_search(&id).is_ok()
+    }
+
+    /// Number of nodes on each side, `side` first.
+    pub fn partition_sizes(&self) -> (usize, usize) {
+        (self.side.len(), self.node_count - self.side.len())
+    }
+
+    /// The edges of `graph` that cross the cut.
+    pub fn cut_edges<'a>(
+        &'a self,
+        graph: &'a Graph,
+    ) -> impl Iterator<Item = (NodeId, NodeId)> + 'a {
+        graph
+            .edges()
+            .iter()
+            .copied()
+            .filter(|&(a, b)| self.contains(a) != self.contains(b))
+    }
+}
+
+impl Graph {
+    /// Global minimum cut of an undirected graph with the Stoer–Wagner
+    /// algorithm, counting every edge as weight one. `None` with fewer than
+    /// two nodes.
+    pub fn min_cut(&self) -> Option<MinCut> {
+        let len = self.len();
+        if len < 2 {
+            return None;
+        }
+        let mut weights = vec![HashMap::<usize, u64>::new(); len];
+        for &(a, b) in &self.edges {
+            let (a, b) = (a as usize, b as usize);
+            if a != b {
+                *weights[a].entry(b).or_default() += 1;
+                *weights[b].entry(a).or_default() += 1;
+            }
+        }
+        // Nodes merged into each remaining node so far
+        let mut groups = (0..len as NodeId).map(|id| vec![id]).collect::<Vec<_>>();
+        let mut active = (0..len).collect::<Vec<_>>();
+        let mut key = vec![0; len];
+        let mut added = vec![false; len];
+        let mut best: Option<(u64, Vec<NodeId>)> = None;
+
+        while active.len() > 1 {
+            // Maximum adjacency order: keep adding the node most tightly
+            // connected to those already added.
+            let mut heap = BinaryHeap::new();
+            for &node in &active {
+                key[node] = 0;
+                added[node] = false;
+                heap.push((0, node));
+            }
+            let (mut s, mut t, mut cut_of_phase) = (usize::MAX, usize::MAX, 0);
+            while let Some((weight, node)) = heap.pop() {
+                if added[node] || weight != key[node] {
+                    continue;
+                }
+                added[node] = true;
+                (s, t, cut_of_phase) = (t, node, weight);
+                for (&neighbour, &w) in &weights[node] {
+                    if !added[neighbour] {
+                        key[neighbour] += w;
+                        heap.push((key[neighbour], neighbour));
+                    }
+                }
+            }
+
+            if best
+                .as_ref()
+                .is_none_or(|(weight, _)| cut_of_phase < *weight)
+            {
+                best = Some((cut_of_phase, groups[t].clone()));
+            }
+
+            // Merge the last node added into the one before it
+            for (neighbour, w) in std::mem::take(&mut weights[t]) {
+                weights[neighbour].remove(&t);
+                if neighbour != s {
+                    *weights[s].entry(neighbour).or_default() += w;
+                    *weights[neighbour].entry(s).or_default() += w;
+                }
+            }
+            let group = std::mem::take(&mut groups[t]);
+            groups[s].extend(group);
+            active.retain(|&node| node != t);
+        }
+
+        best.map(|(weight, side)| MinCut::new(weight, side, len))
+    }
+
+    /// A flow network with capacity one per edge, in both directions when the
+    /// graph is undirected.
+    pub fn to_flow_network(&self) -> FlowNetwork {
+        let mut network = FlowNetwork::new(self.len());
+        for &(a, b) in &self.edges {
+            if self.directed {
+                network.add_edge(a, b, 1);
+            } else {
+                network.add_undirected_edge(a, b, 1);
+            }
+        }
+        network
+    }
+
+    /// The fewest edges to remove to disconnect `sink` from `source`.
+    pub fn st_min_cut(&self, source: NodeId, sink: NodeId) -> MinCut {
+        self.to_flow_network().min_cut(source, sink)
+    }
+}
+
+/// Capacitated network for max-flow. Edges are stored in pairs, so edge `e`
+/// and `e ^ 1` are each other's residual.
+#[derive(Debug, Clone, Default)]
+pub struct FlowNetwork {
+    adjacency: Vec<Vec<usize>>,
+    targets: Vec<NodeId>,
+    capacity: Vec<u64>,
+    residual: Vec<u64>,
+}
+
+impl FlowNetwork {
+    pub fn new(len: usize) -> Self {
+        Self {
+            adjacency: vec![Vec::new(); len],
+            ..Self::default()
+        }
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, capacity: u64) {
+        self.add_pair(from, to, capacity, 0);
+    }
+
+    /// An edge that can carry `capacity` in either direction.
+    pub fn add_undirected_edge(&mut self, a: NodeId, b: NodeId, capacity: u64) {
+        self.add_pair(a, b, capacity, capacity);
+    }
+
+    fn add_pair(&mut self, a: NodeId, b: NodeId, forward: u64, backward: u64) {
+        for (from, to, capacity) in [(a, b, forward), (b, a, backward)] {
+            self.adjacency[from as usize].push(self.targets.len());
+            self.targets.push(to);
+            self.capacity.push(capacity);
+            self.residual.push(capacity);
+        }
+    }
+
+    /// Pushes as much flow as possible from `source` to `sink` with Dinic's
+    /// algorithm and returns how much was added. Flow already pushed by
+    /// earlier calls stays in place.
+    pub fn max_flow(&mut self, source: NodeId, sink: NodeId) -> u64 {
+        let (source, sink) = (source as usize, sink as usize);
+        let mut total = 0;
+        while let Some(level) = self.levels(source, sink) {
+            let mut next = vec![0; self.len()];
+            loop {
+                let pushed = self.augment(source, sink, u64::MAX, &level, &mut next);
+                if pushed == 0 {
+                    break;
+                }
+                total += pushed;
+            }
+        }
+        total
+    }
+
+    /// Breadth-first distances from `source` over edges with residual
+    /// capacity, or `None` once `sink` is unreachable.
+    fn levels(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut level = vec![usize::MAX; self.len()];
+        level[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &edge in &self.adjacency[node] {
+                let target = self.targets[edge] as usize;
+                if self.residual[edge] > 0 && level[target] == usize::MAX {
+                    level[target] = level[node] + 1;
+                    queue.push_back(target);
+                }
+            }
+        }
+        (level[sink] != usize::MAX).then_some(level)
+    }
+
+    fn augment(
+        &mut self,
+        node: usize,
+        sink: usize,
+        limit: u64,
+        level: &[usize],
+        next: &mut [usize],
+    ) -> u64 {
+        if node == sink {
+            return limit;
+        }
+        while next[node] < self.adjacency[node].len() {
+            let edge = self.adjacency[node][next[node]];
+            let target = self.targets[edge] as usize;
+            if self.residual[edge] > 0 && level[target] == level[node] + 1 {
+                let pushed =
+                    self.augment(target, sink, limit.min(self.residual[edge]), level, next);
+                if pushed > 0 {
+                    self.residual[edge] -= pushed;
+                    self.residual[edge ^ 1] += pushed;
+                    return pushed;
+                }
+            }
+            next[node] += 1;
+        }
+        0
+    }
+
+    /// Saturates the network, then splits it into the nodes still reachable
+    /// from `source` and the rest.
+    pub fn min_cut(&mut self, source: NodeId, sink: NodeId) -> MinCut {
+        self.max_flow(source, sink);
+        let mut reachable = vec![false; self.len()];
+        reachable[source as usize] = true;
+        let mut queue = VecDeque::from([source as usize]);
+        while let Some(node) = queue.pop_front() {
+            for &edge in &self.adjacency[node] {
+                let target = self.targets[edge] as usize;
+                if self.residual[edge] > 0 && !reachable[target] {
+                    reachable[target] = true;
+                    queue.push_back(target);
+                }
+            }
+        }
+
+        let weight = (0..self.len())
+            .filter(|&node| reachable[node])
+            .flat_map(|node| &self.adjacency[node])
+            .filter(|&&edge| !reachable[self.targets[edge] as usize])
+            .map(|&edge| self.capacity[edge])
+            .sum();
+        let side = (0..self.len() as NodeId)
+            .filter(|&node| reachable[node as usize])
+            .collect();
+        MinCut::new(weight, side, self.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::{Directed, Undirected};
+
+    #[test]
+    fn test_parse_adjacency() {
+        let graph =
+            Graph::parse_adjacency("you: bbb ccc\nbbb: out\n\nccc: out bbb", ":", true).unwrap();
+        assert_eq!((graph.len(), graph.edge_count()), (4, 5));
+        let you = graph.id("you").unwrap();
+        assert_eq!(graph.name(you), "you");
+        let names = |ids: &[NodeId]| ids.iter().map(|&id| graph.name(id)).collect::<Vec<_>>();
+        assert_eq!(names(graph.neighbours(you)), ["bbb", "ccc"]);
+        assert!(graph.neighbours(graph.id("out").unwrap()).is_empty());
+
+        let arrows = Graph::parse_adjacency("%a -> b, c", "->", true).unwrap();
+        assert_eq!(arrows.neighbours(0), [1, 2]);
+        let pairs = Graph::parse_adjacency("AAA = (BBB, CCC)", "=", true).unwrap();
+        assert_eq!(pairs.name(pairs.neighbours(0)[1]), "CCC");
+
+        assert_eq!(
+            Graph::parse_adjacency("a b", ":", true).unwrap_err(),
+            ParseGraphError::MissingSeparator {
+                line: "a b".to_string(),
+                separator: ":".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_undirected_and_csr() {
+        let graph = Graph::parse_edges("kh-tc\nqp-kh\nde-cg\nka-co", "-", false).unwrap();
+        let kh = graph.id("kh").unwrap();
+        assert_eq!(graph.neighbours(kh).len(), 2);
+
+        let csr = graph.to_csr();
+        assert_eq!(csr.len(), graph.len());
+        for id in graph.nodes() {
+            assert_eq!(csr.neighbours(id), graph.neighbours(id));
+        }
+        assert_eq!(csr.degree(kh), 2);
+    }
+
+    #[test]
+    fn test_to_petgraph() {
+        let graph = Graph::parse_adjacency("a: b c\nb: c", ":", false).unwrap();
+        let undirected = graph.to_petgraph::<Undirected>();
+        assert_eq!((undirected.node_count(), undirected.edge_count()), (3, 3));
+        let b = petgraph::graph::NodeIndex::new(graph.id("b").unwrap() as usize);
+        assert_eq!(undirected[b], "b");
+        assert_eq!(undirected.neighbors(b).count(), 2);
+
+        let directed = graph.to_petgraph::<Directed>();
+        assert_eq!(directed.neighbors(b).count(), 1);
+    }
+
+    fn day25_example() -> Graph {
+        let input = include_str!("../2023/day25/example.txt");
+        Graph::parse_adjacency(input, ":", false).unwrap()
+    }
+
+    #[test]
+    fn test_global_min_cut() {
+        let graph = day25_example();
+        let cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 3);
+        let (a, b) = cut.partition_sizes();
+        assert_eq!(a * b, 54);
+
+        let mut cut_edges = cut
+            .cut_edges(&graph)
+            .map(|(a, b)| {
+                let mut edge = [graph.name(a), graph.name(b)];
+                edge.sort();
+                edge
+            })
+            .collect::<Vec<_>>();
+        cut_edges.sort();
+        assert_eq!(cut_edges, [["bvb", "cmg"], ["hfx", "pzl"], ["jqt", "nvd"]]);
+        assert!(Graph::undirected().min_cut().is_none());
+    }
+
+    #[test]
+    fn test_st_min_cut() {
+        let graph = day25_example();
+        let (jqt, cmg, xhk) = (
+            graph.id("jqt").unwrap(),
+            graph.id("cmg").unwrap(),
+            graph.id("xhk").unwrap(),
+        );
+        let cut = graph.st_min_cut(jqt, cmg);
+        assert_eq!(cut.weight, 3);
+        assert!(cut.contains(jqt) && !cut.contains(cmg));
+        assert_eq!(cut.partition_sizes(), (6, 9));
+        assert!(graph.st_min_cut(jqt, xhk).weight > 3);
+    }
+
+    #[test]
+    fn test_max_flow() {
+        // The network from CLRS figure 26.1
+        let mut network = FlowNetwork::new(6);
+        for (from, to, capacity) in [
+            (0, 1, 16),
+            (0, 2, 13),
+            (2, 1, 4),
+            (1, 3, 12),
+            (3, 2, 9),
+            (2, 4, 14),
+            (4, 3, 7),
+            (3, 5, 20),
+            (4, 5, 4),
+        ] {
+            network.add_edge(from, to, capacity);
+        }
+        let mut cut_network = network.clone();
+        assert_eq!(network.max_flow(0, 5), 23);
+        assert_eq!(network.max_flow(0, 5), 0, "already saturated");
+
+        let cut = cut_network.min_cut(0, 5);
+        assert_eq!(cut.weight, 23);
+        assert_eq!(cut.side, [0, 1, 2, 4]);
+    }
+}
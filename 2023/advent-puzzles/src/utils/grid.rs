@@ -0,0 +1,578 @@
+use std::{
+    fmt::{Display, Write},
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::{Index, IndexMut},
+};
+
+use array2d::Array2D;
+use strum::IntoEnumIterator;
+
+use super::{
+    direction::Direction,
+    map::{Dimensions, GenerateMap, InteractWithPoint, PrintMap, PrintMapWith},
+    point::Point,
+};
+
+/// Owned, row-major 2D grid indexed by [`Point`], with `x` as the column and
+/// `y` as the row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled_with(item: T, width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![item; width * height],
+        }
+    }
+
+    /// Builds a grid from equally long rows.
+    pub fn from_rows(rows: &[Vec<T>]) -> Result<Self, array2d::Error> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(array2d::Error::DimensionMismatch);
+        }
+        Ok(Self {
+            width,
+            height: rows.len(),
+            cells: rows.concat(),
+        })
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, p.x)].clone()
+        })
+    }
+
+    /// Rotates a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        let last_row = self.height as i32 - 1;
+        Self::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, last_row - p.x)].clone()
+        })
+    }
+
+    /// Rotates a quarter turn counter-clockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        let last_column = self.width as i32 - 1;
+        Self::from_fn(self.height, self.width, |p| {
+            self[Point::new(last_column - p.y, p.x)].clone()
+        })
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        let mut rotated = self.clone();
+        rotated.cells.reverse();
+        rotated
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_h(&self) -> Self {
+        let mut flipped = self.clone();
+        flipped
+            .cells
+            .chunks_mut(self.width.max(1))
+            .for_each(<[T]>::reverse);
+        flipped
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_v(&self) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            cells: self.rows().rev().flatten().cloned().collect(),
+        }
+    }
+
+    /// All eight rotations and reflections, starting with the grid itself.
+    /// Symmetric grids yield duplicates.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations = Vec::with_capacity(8);
+        for start in [self.clone(), self.flip_h()] {
+            let mut current = start;
+            for _ in 0..4 {
+                let next = current.rotate_cw();
+                orientations.push(current);
+                current = next;
+            }
+        }
+        orientations
+    }
+
+    /// Copies out the `width` x `height` block whose top-left corner is
+    /// `origin`, or `None` if it does not fit.
+    pub fn sub_grid(&self, origin: Point, width: usize, height: usize) -> Option<Self> {
+        Some(self.window(origin, width, height)?.to_grid())
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn<F: FnMut(Point) -> T>(width: usize, height: usize, mut f: F) -> Self {
+        Self {
+            width,
+            height,
+            cells: (0..width * height)
+                .map(|index| f(Point::from_index(index, width)))
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    fn cell_index(&self, point: &Point) -> Option<usize> {
+        let x = usize::try_from(point.x).ok()?;
+        let y = usize::try_from(point.y).ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.cell_index(point).is_some()
+    }
+
+    pub fn get(&self, point: &Point) -> Option<&T> {
+        self.cells.get(self.cell_index(point)?)
+    }
+
+    pub fn get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        let index = self.cell_index(point)?;
+        self.cells.get_mut(index)
+    }
+
+    /// Replaces the item at `point`, returning the old one, or gives the item
+    /// back if `point` is out of bounds.
+    pub fn replace(&mut self, point: &Point, item: T) -> Result<T, T> {
+        match self.get_mut(point) {
+            Some(cell) => Ok(std::mem::replace(cell, item)),
+            None => Err(item),
+        }
+    }
+
+    /// Swaps two cells. Panics if either point is out of bounds.
+    pub fn swap(&mut self, a: &Point, b: &Point) {
+        let a = self.cell_index(a).expect("Point should be valid");
+        let b = self.cell_index(b).expect("Point should be valid");
+        self.cells.swap(a, b);
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// The items of column `x`, top to bottom. Panics if `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        assert!(x < self.width, "Column {} is outside the grid", x);
+        (0..self.height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Iterates all cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(index, item)| (Point::from_index(index, width), item))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut T)> {
+        let width = self.width;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(index, item)| (Point::from_index(index, width), item))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// The first point, in row-major order, whose item matches `predicate`.
+    pub fn find<P: FnMut(&T) -> bool>(&self, predicate: P) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|index| Point::from_index(index, self.width))
+    }
+
+    /// All points whose item matches `predicate`, in row-major order.
+    pub fn find_all<'a, P: FnMut(&T) -> bool + 'a>(
+        &'a self,
+        mut predicate: P,
+    ) -> impl Iterator<Item = Point> + 'a {
+        self.iter()
+            .filter(move |(_, item)| predicate(item))
+            .map(|(point, _)| point)
+    }
+
+    pub fn count<P: FnMut(&T) -> bool>(&self, mut predicate: P) -> usize {
+        self.cells.iter().filter(|item| predicate(item)).count()
+    }
+
+    /// Borrows the `width` x `height` block whose top-left corner is
+    /// `origin`, or `None` if it does not fit.
+    pub fn window(&self, origin: Point, width: usize, height: usize) -> Option<GridView<'_, T>> {
+        let fits = self.contains(&origin)
+            && origin.x as usize + width <= self.width
+            && origin.y as usize + height <= self.height;
+        fits.then_some(GridView {
+            grid: self,
+            origin,
+            width,
+            height,
+        })
+    }
+
+    /// Every `width` x `height` window that fits, in row-major order of the
+    /// top-left corners.
+    pub fn windows(&self, width: usize, height: usize) -> impl Iterator<Item = GridView<'_, T>> {
+        let columns = (self.width + 1).saturating_sub(width.max(1));
+        let rows = (self.height + 1).saturating_sub(height.max(1));
+        (0..rows).flat_map(move |y| {
+            (0..columns)
+                .filter_map(move |x| self.window(Point::new(x as i32, y as i32), width, height))
+        })
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// The first point, in row-major order, holding `item`.
+    pub fn position(&self, item: &T) -> Option<Point> {
+        self.find(|other| other == item)
+    }
+
+    pub fn positions<'a>(&'a self, item: &'a T) -> impl Iterator<Item = Point> + 'a {
+        self.find_all(move |other| other == item)
+    }
+}
+
+impl<T: Hash> Grid<T> {
+    /// A 64-bit hash of the contents, so cycle detection can remember states
+    /// without cloning whole grids.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(&point)
+            .unwrap_or_else(|| panic!("Point {} is outside the grid", point))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(&point)
+            .unwrap_or_else(|| panic!("Point {} is outside the grid", point))
+    }
+}
+
+impl<T: Clone> From<Array2D<T>> for Grid<T> {
+    fn from(array: Array2D<T>) -> Self {
+        Self {
+            width: array.num_columns(),
+            height: array.num_rows(),
+            cells: array.as_row_major(),
+        }
+    }
+}
+
+impl<T: Clone> From<&Grid<T>> for Array2D<T> {
+    fn from(grid: &Grid<T>) -> Self {
+        Array2D::from_row_major(&grid.cells, grid.height, grid.width)
+            .expect("Grid dimensions should match its cells")
+    }
+}
+
+impl<T: Clone> From<Grid<T>> for Array2D<T> {
+    fn from(grid: Grid<T>) -> Self {
+        Self::from(&grid)
+    }
+}
+
+impl<T: Clone> GenerateMap for Grid<T> {
+    type Item = T;
+
+    fn generate_map<F: FnMut(Point, char) -> Self::Item>(
+        input: &str,
+        mut f: F,
+    ) -> Result<Self, array2d::Error> {
+        let rows: Vec<Vec<T>> = input
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| f(Point::new(x as i32, y as i32), c))
+                    .collect()
+            })
+            .collect();
+
+        Self::from_rows(&rows)
+    }
+}
+
+impl<T> InteractWithPoint for Grid<T> {
+    type Item = T;
+
+    fn get_point(&self, point: &Point) -> Option<&Self::Item> {
+        self.get(point)
+    }
+
+    fn get_point_mut(&mut self, point: &Point) -> Option<&mut Self::Item> {
+        self.get_mut(point)
+    }
+
+    fn get_looping_point(&self, point: &Point) -> &Self::Item {
+        let point = Point::new(
+            point.x.rem_euclid(self.width as i32),
+            point.y.rem_euclid(self.height as i32),
+        );
+        &self[point]
+    }
+
+    fn set_point(&mut self, point: &Point, item: Self::Item) -> Result<(), array2d::Error> {
+        self.replace(point, item)
+            .map(|_| ())
+            .map_err(|_| array2d::Error::IndicesOutOfBounds(point.y as usize, point.x as usize))
+    }
+
+    fn four_neighbours(&self, point: &Point) -> Vec<Point> {
+        if !self.contains(point) {
+            return Vec::new();
+        }
+        Direction::iter()
+            .map(|direction| point.move_in_direction(direction))
+            .filter(|neighbour| self.contains(neighbour))
+            .collect()
+    }
+
+    fn eight_neighbours(&self, point: &Point) -> Vec<Point> {
+        if !self.contains(point) {
+            return Vec::new();
+        }
+        // Same order as the `Array2D` impl: column by column from the top-left
+        let offsets = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+        offsets
+            .iter()
+            .map(|(dx, dy)| Point::new(point.x + dx, point.y + dy))
+            .filter(|neighbour| self.contains(neighbour))
+            .collect()
+    }
+}
+
+impl<T> Dimensions for Grid<T> {
+    fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for item in row {
+                write!(f, "{}", item)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Display> PrintMap for Grid<T> {
+    fn print_map(&self) {
+        print!("{}", self);
+    }
+}
+
+impl<T> PrintMapWith for Grid<T> {
+    type Item = T;
+
+    fn print_with<F: Fn(&Point, &Self::Item, &mut String)>(&self, f: F) {
+        let mut string = String::new();
+        self.iter().for_each(|(point, item)| {
+            f(&point, item, &mut string);
+            if point.x == self.width as i32 - 1 {
+                writeln!(string).unwrap();
+            }
+        });
+        print!("{}", string);
+    }
+}
+
+/// A borrowed rectangular block of a [`Grid`]. Points passed to the view are
+/// relative to its top-left corner.
+#[derive(Debug, Clone, Copy)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Point,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> GridView<'a, T> {
+    /// Top-left corner of the view in the underlying grid.
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, point: &Point) -> Option<&'a T> {
+        let inside =
+            (0..self.width as i32).contains(&point.x) && (0..self.height as i32).contains(&point.y);
+        if inside {
+            self.grid.get(&(self.origin + *point))
+        } else {
+            None
+        }
+    }
+
+    /// The items of row `y` of the view. Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &'a [T] {
+        assert!(y < self.height, "Row {} is outside the view", y);
+        let start = self.origin.x as usize;
+        &self.grid.row(self.origin.y as usize + y)[start..start + self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Iterates the view in row-major order with view-relative points.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &'a T)> + '_ {
+        self.rows().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, item)| (Point::new(x as i32, y as i32), item))
+        })
+    }
+}
+
+impl<T: Clone> GridView<'_, T> {
+    pub fn to_grid(&self) -> Grid<T> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.rows().flatten().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::generate_map("abc\ndef", |_, c| c).unwrap()
+    }
+
+    #[test]
+    fn test_indexing_and_iteration() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 'f');
+        assert_eq!(grid.get(&Point::new(3, 0)), None);
+        assert_eq!(grid.get(&Point::new(0, -1)), None);
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.rows().next_back(), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.position(&'e'), Some(Point::new(1, 1)));
+        assert_eq!(grid.find_all(|c| *c > 'b').count(), 4);
+        assert!(Grid::from_rows(&[vec![1, 2], vec![3]]).is_err());
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.rotate_180().to_string(), "fed\ncba\n");
+        assert_eq!(grid.flip_h().to_string(), "cba\nfed\n");
+        assert_eq!(grid.flip_v().to_string(), "def\nabc\n");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.orientations().len(), 8);
+    }
+
+    #[test]
+    fn test_windows() {
+        let grid = Grid::from_fn(4, 3, |p| p.x + 10 * p.y);
+        assert_eq!(grid.windows(2, 2).count(), 6);
+        let view = grid.window(Point::new(1, 1), 3, 2).unwrap();
+        assert_eq!(view.get(&Point::new(2, 1)), Some(&23));
+        assert_eq!(view.get(&Point::new(3, 0)), None);
+        assert_eq!(view.to_grid().row(0), &[11, 12, 13]);
+        assert!(grid.window(Point::new(2, 2), 3, 1).is_none());
+        assert_eq!(
+            grid.sub_grid(Point::new(0, 0), 1, 3).unwrap(),
+            Grid::from_fn(1, 3, |p| 10 * p.y)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Row 2 is outside the view")]
+    fn test_view_row_out_of_bounds() {
+        let grid = Grid::from_fn(4, 4, |p| p.x + 10 * p.y);
+        grid.window(Point::new(0, 0), 4, 2).unwrap().row(2);
+    }
+
+    #[test]
+    fn test_conversions_and_fingerprint() {
+        let grid = sample();
+        let array: Array2D<char> = grid.clone().into();
+        assert_eq!(array.get(1, 0), Some(&'d'));
+        assert_eq!(Grid::from(array), grid);
+        assert_eq!(grid.fingerprint(), sample().fingerprint());
+        assert_ne!(grid.fingerprint(), grid.flip_h().fingerprint());
+    }
+}
@@ -0,0 +1,224 @@
+use num::PrimInt;
+
+use super::point_3d::{Aabb3D, Point3D};
+
+/// Dense 3D grid indexed by [`Point3D`] with bounds checks, the 3D
+/// counterpart of `Array2D` with [`InteractWithPoint`](super::map::InteractWithPoint).
+/// Coordinates range over `0..width`, `0..depth` and `0..height` on the x, y
+/// and z axes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid3D<T> {
+    width: usize,
+    depth: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid3D<T> {
+    pub fn filled_with(item: T, width: usize, depth: usize, height: usize) -> Self {
+        Self {
+            width,
+            depth,
+            height,
+            cells: vec![item; width * depth * height],
+        }
+    }
+}
+
+impl<T> Grid3D<T> {
+    pub fn from_fn<C, F>(width: usize, depth: usize, height: usize, mut f: F) -> Self
+    where
+        C: PrimInt,
+        F: FnMut(Point3D<C>) -> T,
+    {
+        let cells = (0..width * depth * height)
+            .map(|index| f(Self::point_at(index, width, depth)))
+            .collect();
+        Self {
+            width,
+            depth,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The inclusive bounds of the grid, or `None` if it is empty.
+    pub fn bounds<C: PrimInt>(&self) -> Option<Aabb3D<C>> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(Aabb3D::new(
+            Point3D::new(C::zero(), C::zero(), C::zero()),
+            Point3D::new(
+                C::from(self.width - 1)?,
+                C::from(self.depth - 1)?,
+                C::from(self.height - 1)?,
+            ),
+        ))
+    }
+
+    fn point_at<C: PrimInt>(index: usize, width: usize, depth: usize) -> Point3D<C> {
+        let coordinate = |n: usize| C::from(n).expect("index should fit the coordinate type");
+        Point3D::new(
+            coordinate(index % width),
+            coordinate(index / width % depth),
+            coordinate(index / (width * depth)),
+        )
+    }
+
+    fn index<C: PrimInt>(&self, point: &Point3D<C>) -> Option<usize> {
+        let x = point.x.to_usize()?;
+        let y = point.y.to_usize()?;
+        let z = point.z.to_usize()?;
+        if x < self.width && y < self.depth && z < self.height {
+            Some((z * self.depth + y) * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains_point<C: PrimInt>(&self, point: &Point3D<C>) -> bool {
+        self.index(point).is_some()
+    }
+
+    pub fn get_point<C: PrimInt>(&self, point: &Point3D<C>) -> Option<&T> {
+        self.cells.get(self.index(point)?)
+    }
+
+    pub fn get_point_mut<C: PrimInt>(&mut self, point: &Point3D<C>) -> Option<&mut T> {
+        let index = self.index(point)?;
+        self.cells.get_mut(index)
+    }
+
+    /// Replaces the item at `point`, returning the old one, or gives the item
+    /// back if `point` is out of bounds.
+    pub fn set_point<C: PrimInt>(&mut self, point: &Point3D<C>, item: T) -> Result<T, T> {
+        match self.get_point_mut(point) {
+            Some(cell) => Ok(std::mem::replace(cell, item)),
+            None => Err(item),
+        }
+    }
+
+    /// The face-adjacent neighbours of `point` that are inside the grid.
+    pub fn six_neighbours<C: PrimInt>(&self, point: &Point3D<C>) -> Vec<Point3D<C>> {
+        if !self.contains_point(point) {
+            return Vec::new();
+        }
+        self.offsets(point, false)
+    }
+
+    /// All neighbours of `point` (sharing a face, edge or corner) that are
+    /// inside the grid.
+    pub fn twenty_six_neighbours<C: PrimInt>(&self, point: &Point3D<C>) -> Vec<Point3D<C>> {
+        if !self.contains_point(point) {
+            return Vec::new();
+        }
+        self.offsets(point, true)
+    }
+
+    fn offsets<C: PrimInt>(&self, point: &Point3D<C>, diagonal: bool) -> Vec<Point3D<C>> {
+        let step = |value: C, delta: i8| match delta {
+            -1 => value.checked_sub(&C::one()),
+            1 => value.checked_add(&C::one()),
+            _ => Some(value),
+        };
+        let mut neighbours = Vec::with_capacity(if diagonal { 26 } else { 6 });
+        for dz in -1..=1i8 {
+            for dy in -1..=1i8 {
+                for dx in -1..=1i8 {
+                    let changed = (dx != 0) as u8 + (dy != 0) as u8 + (dz != 0) as u8;
+                    if changed == 0 || (!diagonal && changed > 1) {
+                        continue;
+                    }
+                    let candidate = (|| {
+                        Some(Point3D::new(
+                            step(point.x, dx)?,
+                            step(point.y, dy)?,
+                            step(point.z, dz)?,
+                        ))
+                    })();
+                    if let Some(candidate) = candidate.filter(|p| self.contains_point(p)) {
+                        neighbours.push(candidate);
+                    }
+                }
+            }
+        }
+        neighbours
+    }
+
+    /// Iterates all cells in x, then y, then z order.
+    pub fn iter_all<C: PrimInt>(&self) -> impl Iterator<Item = (Point3D<C>, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (Self::point_at(index, self.width, self.depth), item))
+    }
+
+    pub fn iter_all_mut<C: PrimInt>(&mut self) -> impl Iterator<Item = (Point3D<C>, &mut T)> {
+        let (width, depth) = (self.width, self.depth);
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(index, item)| (Self::point_at(index, width, depth), item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_and_set() {
+        let mut grid = Grid3D::filled_with(0, 2, 3, 4);
+        let point = Point3D::new(1, 2, 3);
+        assert_eq!(grid.set_point(&point, 5), Ok(0));
+        assert_eq!(grid.get_point(&point), Some(&5));
+        assert_eq!(grid.get_point(&Point3D::new(2, 0, 0)), None);
+        assert_eq!(grid.get_point(&Point3D::new(-1, 0, 0)), None);
+        assert_eq!(grid.set_point(&Point3D::new(0, 3, 0), 1), Err(1));
+        assert_eq!(grid.len(), 24);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid3D::filled_with((), 3, 3, 3);
+        assert_eq!(grid.six_neighbours(&Point3D::new(1, 1, 1)).len(), 6);
+        assert_eq!(grid.six_neighbours(&Point3D::new(0u8, 0, 0)).len(), 3);
+        assert_eq!(grid.twenty_six_neighbours(&Point3D::new(1, 1, 1)).len(), 26);
+        assert_eq!(grid.twenty_six_neighbours(&Point3D::new(0, 0, 0)).len(), 7);
+        assert!(grid.six_neighbours(&Point3D::new(5, 0, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_iteration_order() {
+        let grid = Grid3D::from_fn(2, 2, 2, |p: Point3D<i32>| p.x + 10 * p.y + 100 * p.z);
+        let points = grid.iter_all::<i32>().collect::<Vec<_>>();
+        assert_eq!(points[1], (Point3D::new(1, 0, 0), &1));
+        assert_eq!(points[2], (Point3D::new(0, 1, 0), &10));
+        assert_eq!(points[7], (Point3D::new(1, 1, 1), &111));
+        assert_eq!(
+            grid.bounds::<i32>(),
+            Some(Aabb3D::new(Point3D::new(0, 0, 0), Point3D::new(1, 1, 1)))
+        );
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use array2d::Array2D;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use thiserror::Error;
+
+use super::{
+    map::{PrintMap, PrintMapWith},
+    point::Point,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseHexError {
+    #[error("invalid hex direction: {0:?}")]
+    InvalidDirection(String),
+}
+
+/// The six neighbours of a flat-topped hex, clockwise from North.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, EnumIter)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl HexDirection {
+    const CLOCKWISE: [HexDirection; 6] = [
+        HexDirection::North,
+        HexDirection::NorthEast,
+        HexDirection::SouthEast,
+        HexDirection::South,
+        HexDirection::SouthWest,
+        HexDirection::NorthWest,
+    ];
+
+    fn rotate(&self, steps: usize) -> Self {
+        Self::CLOCKWISE[(*self as usize + steps) % 6]
+    }
+
+    pub fn turn_right(&self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn turn_left(&self) -> Self {
+        self.rotate(5)
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(3)
+    }
+
+    /// The axial `(dq, dr)` of one step.
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            HexDirection::North => (0, -1),
+            HexDirection::NorthEast => (1, -1),
+            HexDirection::SouthEast => (1, 0),
+            HexDirection::South => (0, 1),
+            HexDirection::SouthWest => (-1, 1),
+            HexDirection::NorthWest => (-1, 0),
+        }
+    }
+}
+
+/// Accepts `n`, `ne`, `se`, `s`, `sw` and `nw` in any case.
+impl FromStr for HexDirection {
+    type Err = ParseHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "n" => Ok(HexDirection::North),
+            "ne" => Ok(HexDirection::NorthEast),
+            "se" => Ok(HexDirection::SouthEast),
+            "s" => Ok(HexDirection::South),
+            "sw" => Ok(HexDirection::SouthWest),
+            "nw" => Ok(HexDirection::NorthWest),
+            _ => Err(ParseHexError::InvalidDirection(s.to_string())),
+        }
+    }
+}
+
+impl Display for HexDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            HexDirection::North => "n",
+            HexDirection::NorthEast => "ne",
+            HexDirection::SouthEast => "se",
+            HexDirection::South => "s",
+            HexDirection::SouthWest => "sw",
+            HexDirection::NorthWest => "nw",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Parses comma separated steps such as `ne,ne,s,sw`.
+pub fn parse_steps(input: &str) -> Result<Vec<HexDirection>, ParseHexError> {
+    input
+        .trim()
+        .split(',')
+        .map(|step| step.trim().parse())
+        .collect()
+}
+
+/// A hex in axial coordinates. The implied third cube coordinate is
+/// `s = -q - r`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// Creates a hex from cube coordinates, or `None` if `q + r + s != 0`.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Option<Self> {
+        (q + r + s == 0).then_some(Self::new(q, r))
+    }
+
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn move_in_direction(&self, direction: HexDirection) -> Self {
+        self.move_n_in_direction(direction, 1)
+    }
+
+    pub fn move_n_in_direction(&self, direction: HexDirection, n: i32) -> Self {
+        let (dq, dr) = direction.delta();
+        Self::new(self.q + dq * n, self.r + dr * n)
+    }
+
+    /// The six neighbours, clockwise from North.
+    pub fn neighbours(&self) -> [Self; 6] {
+        HexDirection::CLOCKWISE.map(|direction| self.move_in_direction(direction))
+    }
+
+    /// Number of steps between two hexes.
+    pub fn distance(&self, other: &Self) -> usize {
+        let (q, r, s) = (*self - *other).cube();
+        (q.unsigned_abs() + r.unsigned_abs() + s.unsigned_abs()) as usize / 2
+    }
+
+    /// The hexes exactly `radius` steps away, clockwise from the northern
+    /// corner. A radius of 0 yields the hex itself.
+    pub fn ring(&self, radius: usize) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let radius = radius as i32;
+        let mut hex = self.move_n_in_direction(HexDirection::North, radius);
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        for side in HexDirection::iter() {
+            let direction = side.turn_right().turn_right();
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex.move_in_direction(direction);
+            }
+        }
+        ring
+    }
+
+    /// All hexes within `radius` steps, ordered by ring from the centre out.
+    pub fn spiral(&self, radius: usize) -> Vec<Self> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// Position in doubled coordinates, where every column is a hex column
+    /// and neighbours in a column are two rows apart.
+    pub fn to_doubled(&self) -> Point {
+        Point::new(self.q, 2 * self.r + self.q)
+    }
+
+    /// Inverse of [`Hex::to_doubled`], or `None` for a point between hexes.
+    pub fn from_doubled(point: &Point) -> Option<Self> {
+        let r = point.y - point.x;
+        (r % 2 == 0).then_some(Self::new(point.x, r / 2))
+    }
+}
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl Add<HexDirection> for Hex {
+    type Output = Self;
+
+    fn add(self, rhs: HexDirection) -> Self::Output {
+        self.move_in_direction(rhs)
+    }
+}
+
+impl AddAssign<HexDirection> for Hex {
+    fn add_assign(&mut self, rhs: HexDirection) {
+        *self = *self + rhs;
+    }
+}
+
+/// Sparse map of hexes, for puzzles where the area is unbounded or only
+/// partially filled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexMap<T> {
+    cells: HashMap<Hex, T>,
+}
+
+impl<T> Default for HexMap<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> HexMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, hex: &Hex) -> Option<&T> {
+        self.cells.get(hex)
+    }
+
+    pub fn get_mut(&mut self, hex: &Hex) -> Option<&mut T> {
+        self.cells.get_mut(hex)
+    }
+
+    pub fn insert(&mut self, hex: Hex, item: T) -> Option<T> {
+        self.cells.insert(hex, item)
+    }
+
+    pub fn remove(&mut self, hex: &Hex) -> Option<T> {
+        self.cells.remove(hex)
+    }
+
+    pub fn contains(&self, hex: &Hex) -> bool {
+        self.cells.contains_key(hex)
+    }
+
+    pub fn entry(&mut self, hex: Hex) -> std::collections::hash_map::Entry<'_, Hex, T> {
+        self.cells.entry(hex)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Hex, &T)> {
+        self.cells.iter()
+    }
+
+    /// The occupied neighbours of `hex`.
+    pub fn neighbours(&self, hex: &Hex) -> impl Iterator<Item = (Hex, &T)> {
+        hex.neighbours()
+            .into_iter()
+            .filter_map(|neighbour| Some((neighbour, self.cells.get(&neighbour)?)))
+    }
+
+    /// The smallest and largest [`Hex::to_doubled`] positions, or `None` if
+    /// the map is empty.
+    fn doubled_bounds(&self) -> Option<(Point, Point)> {
+        let mut points = self.cells.keys().map(Hex::to_doubled);
+        let first = points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+}
+
+impl<T: Clone> HexMap<T> {
+    /// Lays the map out on a square grid using doubled coordinates shifted so
+    /// the top-left hex lands at `(0, 0)`. Cells between hexes and missing
+    /// hexes are `None`.
+    pub fn to_map(&self) -> Array2D<Option<T>> {
+        let Some((min, max)) = self.doubled_bounds() else {
+            return Array2D::filled_with(None, 0, 0);
+        };
+        let rows = (max.y - min.y + 1) as usize;
+        let columns = (max.x - min.x + 1) as usize;
+        let mut map = Array2D::filled_with(None, rows, columns);
+        for (hex, item) in &self.cells {
+            let point = hex.to_doubled() - min;
+            map[(point.y as usize, point.x as usize)] = Some(item.clone());
+        }
+        map
+    }
+}
+
+impl<T> FromIterator<(Hex, T)> for HexMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Hex, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Prints the [`HexMap::to_map`] layout, with blanks where there is no hex.
+impl<T: Clone + Display> PrintMap for HexMap<T> {
+    fn print_map(&self) {
+        self.print_with(|_, item, string| match item {
+            Some(item) => string.push_str(&item.to_string()),
+            None => string.push(' '),
+        });
+    }
+}
+
+/// Prints the [`HexMap::to_map`] layout, so `f` receives points in that
+/// shifted, doubled space.
+impl<T: Clone> PrintMapWith for HexMap<T> {
+    type Item = Option<T>;
+
+    fn print_with<F: Fn(&Point, &Self::Item, &mut String)>(&self, f: F) {
+        self.to_map().print_with(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_walk() {
+        let steps = parse_steps("ne,ne,s,s\n").unwrap();
+        let end = steps
+            .into_iter()
+            .fold(Hex::default(), |hex, step| hex + step);
+        assert_eq!(end.distance(&Hex::default()), 2);
+        assert_eq!(
+            parse_steps("se,sw,se,sw,sw")
+                .unwrap()
+                .into_iter()
+                .fold(Hex::default(), Add::add)
+                .distance(&Hex::default()),
+            3
+        );
+        assert!(parse_steps("ne,x").is_err());
+    }
+
+    #[test]
+    fn test_coordinates() {
+        let hex = Hex::new(2, -3);
+        assert_eq!(hex.cube(), (2, -3, 1));
+        assert_eq!(Hex::from_cube(2, -3, 1), Some(hex));
+        assert_eq!(Hex::from_cube(1, 1, 1), None);
+        assert_eq!(Hex::from_doubled(&hex.to_doubled()), Some(hex));
+        for direction in HexDirection::iter() {
+            assert_eq!(hex + direction + direction.opposite(), hex);
+            assert_eq!(hex.distance(&(hex + direction)), 1);
+        }
+    }
+
+    #[test]
+    fn test_rings_and_spirals() {
+        let center = Hex::new(1, 1);
+        assert_eq!(center.ring(0), vec![center]);
+        assert_eq!(center.ring(1).len(), 6);
+        assert!(center.ring(3).iter().all(|hex| hex.distance(&center) == 3));
+        assert_eq!(center.ring(3).len(), 18);
+        let spiral = center.spiral(2);
+        assert_eq!(spiral.len(), 19);
+        assert_eq!(
+            spiral
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            19
+        );
+    }
+
+    #[test]
+    fn test_hex_map() {
+        let map = Hex::default()
+            .spiral(1)
+            .into_iter()
+            .map(|hex| (hex, 'x'))
+            .collect::<HexMap<_>>();
+        assert_eq!(map.neighbours(&Hex::default()).count(), 6);
+        assert_eq!(map.neighbours(&Hex::new(0, -1)).count(), 3);
+        let layout = map.to_map();
+        assert_eq!((layout.num_columns(), layout.num_rows()), (3, 5));
+        assert_eq!(layout.elements_row_major_iter().flatten().count(), 7);
+    }
+}
@@ -0,0 +1,169 @@
+use num::PrimInt;
+
+use super::{interval::Interval, point_3d::Aabb3D};
+
+/// Axis-aligned box in `N` dimensions, one half-open [`Interval`] per axis.
+/// Empty as soon as any axis is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HyperBox<const N: usize, T> {
+    pub axes: [Interval<T>; N],
+}
+
+impl<const N: usize, T: PrimInt> HyperBox<N, T> {
+    pub fn new(axes: [Interval<T>; N]) -> Self {
+        Self { axes }
+    }
+
+    /// The box from `min` to `max`, both corners included.
+    pub fn inclusive(min: [T; N], max: [T; N]) -> Self {
+        Self::new(std::array::from_fn(|axis| {
+            Interval::inclusive(min[axis], max[axis])
+        }))
+    }
+
+    pub fn axis(&self, axis: usize) -> Interval<T> {
+        self.axes[axis]
+    }
+
+    /// A copy with one axis replaced.
+    pub fn with_axis(&self, axis: usize, interval: Interval<T>) -> Self {
+        let mut axes = self.axes;
+        axes[axis] = interval;
+        Self::new(axes)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(Interval::is_empty)
+    }
+
+    /// Number of points inside. Pick a `T` wide enough for the product.
+    pub fn volume(&self) -> T {
+        self.axes
+            .iter()
+            .fold(T::one(), |volume, interval| volume * interval.len())
+    }
+
+    pub fn contains(&self, point: &[T; N]) -> bool {
+        self.axes
+            .iter()
+            .zip(point)
+            .all(|(interval, &value)| interval.contains(value))
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        other.is_empty()
+            || self
+                .axes
+                .iter()
+                .zip(&other.axes)
+                .all(|(outer, inner)| outer.contains_interval(inner))
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut axes = self.axes;
+        for (axis, interval) in axes.iter_mut().enumerate() {
+            *interval = interval.intersection(&other.axes[axis])?;
+        }
+        Some(Self::new(axes))
+    }
+
+    /// Cuts the box along `axis` into the part below `value` and the part
+    /// from `value` up. Either side is `None` when it would be empty.
+    pub fn split_at(&self, axis: usize, value: T) -> (Option<Self>, Option<Self>) {
+        let interval = self.axes[axis];
+        let below = Interval::new(interval.start, value.min(interval.end));
+        let above = Interval::new(value.max(interval.start), interval.end);
+        let side = |part: Interval<T>| {
+            let part = self.with_axis(axis, part);
+            (!part.is_empty()).then_some(part)
+        };
+        (side(below), side(above))
+    }
+
+    /// `self` with `other` cut out, as at most `2 * N` disjoint boxes.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        };
+        // Peel off the slabs outside the overlap one axis at a time.
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for (axis, interval) in overlap.axes.iter().enumerate() {
+            let (below, middle) = rest.split_at(axis, interval.start);
+            let (middle, above) = middle
+                .expect("overlap is non-empty")
+                .split_at(axis, interval.end);
+            pieces.extend(below);
+            pieces.extend(above);
+            rest = middle.expect("overlap is non-empty");
+        }
+        pieces
+    }
+}
+
+impl<T: PrimInt> From<Aabb3D<T>> for HyperBox<3, T> {
+    fn from(aabb: Aabb3D<T>) -> Self {
+        Self::inclusive(aabb.min.into(), aabb.max.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::point_3d::Point3D;
+
+    #[test]
+    fn test_split_and_volume() {
+        let cube = HyperBox::inclusive([1, 1, 1, 1], [4000, 4000, 4000, 4000]);
+        assert_eq!(cube.volume(), 256_000_000_000_000u64);
+
+        let (low, high) = cube.split_at(1, 2091);
+        let (low, high) = (low.unwrap(), high.unwrap());
+        assert_eq!(low.axis(1), Interval::new(1, 2091));
+        assert_eq!(high.axis(1), Interval::new(2091, 4001));
+        assert_eq!(low.volume() + high.volume(), cube.volume());
+        assert!(cube.contains_box(&low) && !low.overlaps(&high));
+        assert!(low.contains(&[1, 2090, 4000, 1]) && !low.contains(&[1, 2091, 1, 1]));
+
+        assert_eq!(cube.split_at(0, 1), (None, Some(cube)));
+        assert_eq!(cube.split_at(0, 5000), (Some(cube), None));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let a = HyperBox::inclusive([0, 0, 0], [9, 9, 9]);
+        let b = HyperBox::inclusive([5, -5, 3], [20, 4, 6]);
+        let overlap = a.intersection(&b).unwrap();
+        assert_eq!(overlap, HyperBox::inclusive([5, 0, 3], [9, 4, 6]));
+
+        let pieces = a.subtract(&b);
+        assert_eq!(pieces.len(), 4);
+        let total = pieces.iter().map(HyperBox::volume).sum::<i32>();
+        assert_eq!(total, a.volume() - overlap.volume());
+        for (i, piece) in pieces.iter().enumerate() {
+            assert!(a.contains_box(piece) && !piece.overlaps(&b));
+            assert!(pieces[i + 1..].iter().all(|other| !piece.overlaps(other)));
+        }
+
+        let inner = HyperBox::inclusive([2, 2], [3, 3]);
+        let outer = HyperBox::inclusive([0, 0], [5, 5]);
+        assert_eq!(outer.subtract(&inner).len(), 4);
+        assert!(inner.subtract(&outer).is_empty());
+        assert_eq!(
+            inner.subtract(&HyperBox::inclusive([9, 9], [9, 9])),
+            vec![inner]
+        );
+    }
+
+    #[test]
+    fn test_from_aabb() {
+        let aabb = Aabb3D::new(Point3D::new(1u16, 2, 3), Point3D::new(1, 4, 3));
+        let hyper_box = HyperBox::from(aabb);
+        assert_eq!(hyper_box.volume(), 3);
+        assert!(hyper_box.contains(&[1, 4, 3]));
+    }
+}
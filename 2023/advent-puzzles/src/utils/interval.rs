@@ -0,0 +1,379 @@
+use std::{
+    fmt::Display,
+    ops::{Range, RangeInclusive},
+};
+
+use num::PrimInt;
+
+/// Half-open interval `start..end`. Empty when `end <= start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// The interval `first..=last`.
+    pub fn inclusive(first: T, last: T) -> Self {
+        Self::new(first, last + T::one())
+    }
+
+    /// The interval starting at `start` covering `length` values.
+    pub fn with_length(start: T, length: T) -> Self {
+        Self::new(start, start + length)
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The shared part of both intervals, `None` if they don't overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let overlap = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!overlap.is_empty()).then_some(overlap)
+    }
+}
+
+impl<T: PrimInt> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for Interval<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        Self::inclusive(*range.start(), *range.end())
+    }
+}
+
+impl<T> From<Interval<T>> for Range<T> {
+    fn from(interval: Interval<T>) -> Self {
+        interval.start..interval.end
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A set of values stored as sorted, disjoint, non-touching intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The normalized intervals, in increasing order.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn total_length(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |total, interval| total + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    /// The largest value in the set.
+    pub fn max(&self) -> Option<T> {
+        self.intervals
+            .last()
+            .map(|interval| interval.end - T::one())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        if interval.is_empty() {
+            return true;
+        }
+        let index = self.intervals.partition_point(|i| i.end <= interval.start);
+        self.intervals
+            .get(index)
+            .is_some_and(|i| i.contains_interval(interval))
+    }
+
+    /// Adds `interval`, merging it with everything it overlaps or touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, i| {
+                Interval::new(merged.start.min(i.start), merged.end.max(i.end))
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// Removes every value of `interval` from the set.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let first = self.intervals.partition_point(|i| i.end <= interval.start);
+        let last = self.intervals.partition_point(|i| i.start < interval.end);
+        let mut kept = Vec::with_capacity(2);
+        if let Some(head) = self.intervals[first..last].first() {
+            kept.push(Interval::new(head.start, interval.start));
+        }
+        if let Some(tail) = self.intervals[first..last].last() {
+            kept.push(Interval::new(interval.end, tail.end));
+        }
+        kept.retain(|i| !i.is_empty());
+        self.intervals.splice(first..last, kept);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        other.iter().for_each(|&interval| union.insert(interval));
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+        let mut intervals = Vec::new();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            intervals.extend(x.intersection(y));
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { intervals }
+    }
+
+    /// Values in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        other
+            .iter()
+            .for_each(|&interval| difference.remove(interval));
+        difference
+    }
+}
+
+impl<T: PrimInt, I: Into<Interval<T>>> FromIterator<I> for IntervalSet<T> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: PrimInt, I: Into<Interval<T>>> Extend<I> for IntervalSet<T> {
+    fn extend<It: IntoIterator<Item = I>>(&mut self, iter: It) {
+        iter.into_iter()
+            .for_each(|interval| self.insert(interval.into()));
+    }
+}
+
+/// Piecewise translation of values: each rule moves a source interval so it
+/// starts at a destination value, and values outside every rule map to
+/// themselves. Source intervals should not overlap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    /// `(source, destination start)`, sorted by source start.
+    rules: Vec<(Interval<T>, T)>,
+}
+
+impl<T> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self { rules: Vec::new() }
+    }
+}
+
+impl<T: PrimInt> RangeMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, source: Interval<T>, destination: T) {
+        let index = self.rules.partition_point(|(s, _)| s.start < source.start);
+        self.rules.insert(index, (source, destination));
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let index = self.rules.partition_point(|(s, _)| s.start <= value);
+        match index.checked_sub(1).map(|i| self.rules[i]) {
+            Some((source, destination)) if source.contains(value) => {
+                value - source.start + destination
+            }
+            _ => value,
+        }
+    }
+
+    /// Maps every value of `set` at once, splitting intervals where rules
+    /// start and end.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut unmapped = set.clone();
+        let mut mapped = IntervalSet::new();
+        for &(source, destination) in &self.rules {
+            let covered = set.intersection(&IntervalSet::from_iter([source]));
+            for interval in covered.iter() {
+                let start = interval.start - source.start + destination;
+                mapped.insert(Interval::with_length(start, interval.len()));
+            }
+            unmapped.remove(source);
+        }
+        mapped.union(&unmapped)
+    }
+}
+
+impl<T: PrimInt> FromIterator<(Interval<T>, T)> for RangeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Interval<T>, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        iter.into_iter()
+            .for_each(|(source, destination)| map.insert(source, destination));
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(u32, u32)]) -> IntervalSet<u32> {
+        intervals.iter().map(|&(start, end)| start..end).collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::from(3..=7);
+        assert_eq!((a.start, a.end, a.len()), (3, 8, 5));
+        assert!(a.contains(7) && !a.contains(8));
+        assert_eq!(a.intersection(&(5..20).into()), Some(Interval::new(5, 8)));
+        assert_eq!(a.intersection(&(8..20).into()), None);
+        assert_eq!(Interval::new(5, 2).len(), 0);
+        assert_eq!(a.to_string(), "3..8");
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut merged = set(&[(0, 10), (3, 7), (7, 12), (15, 20), (11, 16)]);
+        assert_eq!(merged, set(&[(0, 20)]));
+        merged.insert(Interval::new(20, 25));
+        merged.insert(Interval::new(30, 31));
+        assert_eq!(
+            merged.intervals(),
+            &[Interval::new(0, 25), Interval::new(30, 31)]
+        );
+
+        merged.remove(Interval::new(5, 10));
+        merged.remove(Interval::new(24, 31));
+        assert_eq!(merged, set(&[(0, 5), (10, 24)]));
+        assert_eq!(merged.total_length(), 19);
+        assert_eq!((merged.min(), merged.max()), (Some(0), Some(23)));
+        assert!(merged.contains(4) && !merged.contains(5) && merged.contains(10));
+        assert!(merged.contains_interval(&Interval::new(12, 24)));
+        assert!(!merged.contains_interval(&Interval::new(3, 12)));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 5), (10, 15), (20, 25)]);
+        let b = set(&[(3, 12), (14, 22)]);
+        assert_eq!(a.union(&b), set(&[(0, 25)]));
+        assert_eq!(
+            a.intersection(&b),
+            set(&[(3, 5), (10, 12), (14, 15), (20, 22)])
+        );
+        assert_eq!(a.difference(&b), set(&[(0, 3), (12, 14), (22, 25)]));
+        assert_eq!(b.difference(&a), set(&[(5, 10), (15, 20)]));
+    }
+
+    #[test]
+    fn test_range_map() {
+        // 2023 day 5 seed-to-soil map
+        let map = [
+            (Interval::with_length(98, 2), 50),
+            (Interval::with_length(50, 48), 52),
+        ]
+        .into_iter()
+        .collect::<RangeMap<u64>>();
+        assert_eq!(
+            [79, 14, 55, 13, 99].map(|seed| map.map(seed)),
+            [81, 14, 57, 13, 51]
+        );
+
+        let seeds = [(79, 14), (55, 13)]
+            .into_iter()
+            .map(|(start, length)| Interval::with_length(start, length))
+            .collect::<IntervalSet<_>>();
+        let soil = map.map_set(&seeds);
+        assert_eq!(soil.total_length(), 27);
+        assert_eq!(
+            soil.intervals(),
+            &[Interval::new(57, 70), Interval::new(81, 95)]
+        );
+
+        let spanning = IntervalSet::from_iter([Interval::new(40, 100)]);
+        assert_eq!(
+            map.map_set(&spanning).intervals(),
+            &[Interval::new(40, 100)],
+            "the two rules swap places within the span"
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use strum::IntoEnumIterator;
+
+use super::{
+    direction::Direction,
+    map::{Dimensions, InteractWithPoint},
+    point::Point,
+};
+
+/// A maze contracted to its branch points. Nodes are cells with three or more
+/// open neighbours plus any explicitly requested points, and each edge is a
+/// corridor between two nodes weighted by its number of steps.
+#[derive(Debug, Clone)]
+pub struct JunctionGraph {
+    points: Vec<Point>,
+    index: HashMap<Point, usize>,
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl JunctionGraph {
+    /// Contracts the corridors of `map`. `can_step(from, direction, to_item)`
+    /// decides whether a single step is allowed. A cell is open if some
+    /// neighbour may step onto it, and two open cells are connected if a step
+    /// is allowed either way, but an edge is only added in the directions
+    /// where every step along the corridor is allowed. Points in `nodes`
+    /// (usually the start and end) always become nodes. Corridors that
+    /// dead-end away from a node are dropped.
+    pub fn new<M, N, F>(map: &M, nodes: N, can_step: F) -> Self
+    where
+        M: InteractWithPoint + Dimensions,
+        N: IntoIterator<Item = Point>,
+        F: Fn(&Point, Direction, &M::Item) -> bool,
+    {
+        let step = |from: &Point, direction: Direction| {
+            let to = *from + direction;
+            map.get_point(&to)
+                .is_some_and(|item| can_step(from, direction, item))
+        };
+        let (width, height) = map.dimensions();
+        let open = (0..width * height)
+            .map(|i| {
+                let point = Point::from_index(i, width);
+                Direction::iter().any(|d| step(&(point + d.opposite()), d))
+            })
+            .collect::<Vec<_>>();
+        let connected = |from: &Point, direction: Direction| {
+            let to = *from + direction;
+            map.get_point(&to).is_some()
+                && open[to.index(width)]
+                && (step(from, direction) || step(&to, direction.opposite()))
+        };
+
+        let mut points = nodes.into_iter().collect::<Vec<_>>();
+        points.extend(
+            (0..width * height)
+                .filter(|&i| open[i])
+                .map(|i| Point::from_index(i, width))
+                .filter(|point| Direction::iter().filter(|&d| connected(point, d)).count() >= 3),
+        );
+        let mut index = HashMap::new();
+        points.retain(|point| {
+            let next = index.len();
+            index.insert(*point, next).is_none()
+        });
+
+        let mut edges = vec![Vec::new(); points.len()];
+        for (from, start) in points.iter().enumerate() {
+            for direction in Direction::iter() {
+                if !step(start, direction) {
+                    continue;
+                }
+                let mut current = *start + direction;
+                let mut facing = direction;
+                let mut length = 1;
+                let end = loop {
+                    if let Some(&to) = index.get(&current) {
+                        break Some(to);
+                    }
+                    let onwards = Direction::iter()
+                        .filter(|&d| d != facing.opposite())
+                        .filter(|&d| connected(&current, d))
+                        .collect::<Vec<_>>();
+                    match onwards[..] {
+                        [d] if step(&current, d) => {
+                            current = current + d;
+                            facing = d;
+                            length += 1;
+                        }
+                        _ => break None,
+                    }
+                };
+                if let Some(to) = end.filter(|&to| to != from) {
+                    edges[from].push((to, length));
+                }
+            }
+        }
+
+        Self {
+            points,
+            index,
+            edges,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Index of the node at `point`.
+    pub fn node(&self, point: &Point) -> Option<usize> {
+        self.index.get(point).copied()
+    }
+
+    pub fn point(&self, node: usize) -> Point {
+        self.points[node]
+    }
+
+    /// Outgoing corridors of `node` as `(target, length)` pairs.
+    pub fn edges(&self, node: usize) -> &[(usize, usize)] {
+        &self.edges[node]
+    }
+
+    /// Length of the longest path from `from` to `to` that visits no node
+    /// twice, or `None` if `to` cannot be reached. Panics on graphs with more
+    /// than 64 nodes.
+    pub fn longest_path(&self, from: usize, to: usize) -> Option<usize> {
+        assert!(
+            self.len() <= 64,
+            "longest_path supports at most 64 nodes, got {}",
+            self.len()
+        );
+
+        // Every unvisited node can still add at most its longest incoming
+        // corridor, which gives an upper bound to prune against.
+        let mut best_incoming = vec![0; self.len()];
+        let mut into_target = Vec::new();
+        for (node, edges) in self.edges.iter().enumerate() {
+            for &(next, length) in edges {
+                best_incoming[next] = best_incoming[next].max(length);
+                if next == to {
+                    into_target.push(node);
+                }
+            }
+        }
+        into_target.dedup();
+        // If the target is only reachable through one node, arriving there
+        // means going straight to the target, or it would be cut off.
+        let gate = match into_target[..] {
+            [gate] => Some(gate),
+            _ => None,
+        };
+
+        let mut longest = Search {
+            graph: self,
+            to,
+            gate,
+            best_incoming: &best_incoming,
+            best: None,
+        };
+        let remaining = best_incoming.iter().sum::<usize>() - best_incoming[from];
+        longest.visit(from, 1 << from, 0, remaining);
+        longest.best
+    }
+}
+
+struct Search<'a> {
+    graph: &'a JunctionGraph,
+    to: usize,
+    gate: Option<usize>,
+    best_incoming: &'a [usize],
+    best: Option<usize>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, node: usize, visited: u64, length: usize, remaining: usize) {
+        if node == self.to {
+            self.best = self.best.max(Some(length));
+            return;
+        }
+        if self.best.is_some_and(|best| length + remaining <= best) {
+            return;
+        }
+        for &(next, edge) in &self.graph.edges[node] {
+            if visited & (1 << next) != 0 || (self.gate == Some(node) && next != self.to) {
+                continue;
+            }
+            self.visit(
+                next,
+                visited | (1 << next),
+                length + edge,
+                remaining - self.best_incoming[next],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use array2d::Array2D;
+
+    use super::*;
+    use crate::utils::map::GenerateMap;
+
+    const HIKE: &str = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+
+    fn hike_graph(slippery: bool) -> JunctionGraph {
+        let map = Array2D::generate_map(HIKE, |_, c| c).unwrap();
+        let ends = [Point::new(1, 0), Point::new(21, 22)];
+        JunctionGraph::new(&map, ends, |_, direction, c| match c {
+            '#' => false,
+            '.' => true,
+            slope => !slippery || Direction::try_from(*slope).is_ok_and(|d| d == direction),
+        })
+    }
+
+    #[test]
+    fn test_contraction() {
+        let graph = hike_graph(false);
+        assert_eq!(graph.len(), 9);
+        let start = graph.node(&Point::new(1, 0)).unwrap();
+        let junction = graph.node(&Point::new(3, 5)).unwrap();
+        assert_eq!(graph.edges(start), &[(junction, 15)]);
+        assert_eq!(graph.point(junction), Point::new(3, 5));
+        assert!(graph.edges(junction).contains(&(start, 15)));
+
+        let slippery = hike_graph(true);
+        let junction = slippery.node(&Point::new(3, 5)).unwrap();
+        assert!(slippery.edges(junction).iter().all(|&(to, _)| to != 0));
+    }
+
+    #[test]
+    fn test_longest_path() {
+        for (slippery, expected) in [(true, 94), (false, 154)] {
+            let graph = hike_graph(slippery);
+            let start = graph.node(&Point::new(1, 0)).unwrap();
+            let end = graph.node(&Point::new(21, 22)).unwrap();
+            assert_eq!(graph.longest_path(start, end), Some(expected));
+            assert_eq!(
+                graph.longest_path(end, start),
+                Some(expected).filter(|_| !slippery)
+            );
+        }
+    }
+}
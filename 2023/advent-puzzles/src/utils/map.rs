@@ -50,10 +50,17 @@ pub trait InteractWithPoint {
     fn four_neighbours(&self, point: &Point) -> Vec<Point>;
 
     /// Returns the eight neighbours (including diagonals) of `point`
-    /// that are inside the bounds of the map.
+    /// that are inside the bounds of the map, column by column from the
+    /// top-left: NW, W, SW, N, S, NE, E, SE.
     fn eight_neighbours(&self, point: &Point) -> Vec<Point>;
 }
 
+/// Maps with a known size, so that every point can be visited by index.
+pub trait Dimensions {
+    /// Returns the `(width, height)` of the map.
+    fn dimensions(&self) -> (usize, usize);
+}
+
 impl<T> InteractWithPoint for Array2D<T> {
     type Item = T;
 
@@ -119,6 +126,12 @@ impl<T> InteractWithPoint for Array2D<T> {
     }
 }
 
+impl<T> Dimensions for Array2D<T> {
+    fn dimensions(&self) -> (usize, usize) {
+        (self.num_columns(), self.num_rows())
+    }
+}
+
 pub trait IterAll {
     type Item;
     fn iter_all(&self) -> MapIter<'_, Self::Item>;
@@ -194,3 +207,46 @@ where
         print!("{}", string);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::grid::Grid;
+
+    #[test]
+    fn test_eight_neighbours_order() {
+        let map = Array2D::filled_with('.', 3, 3);
+        let xy = |points: Vec<Point>| points.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        assert_eq!(
+            xy(map.eight_neighbours(&Point::new(1, 1))),
+            [
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 2),
+                (2, 0),
+                (2, 1),
+                (2, 2)
+            ]
+        );
+        assert_eq!(
+            xy(map.eight_neighbours(&Point::new(0, 0))),
+            [(0, 1), (1, 0), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_grid_eight_neighbours_match_array() {
+        let array = Array2D::filled_with('.', 4, 3);
+        let grid = Grid::filled_with('.', 3, 4);
+        for point in (0..12).map(|index| Point::from_index(index, 3)) {
+            assert_eq!(
+                grid.eight_neighbours(&point),
+                array.eight_neighbours(&point),
+                "neighbours of {}",
+                point
+            );
+        }
+    }
+}
@@ -1,11 +1,42 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+use std::thread::LocalKey;
+use std::time::{Duration, Instant};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Default directory (relative to the runner's working directory) holding
+/// caches of `#[memoize(persist)]` functions.
+pub const DEFAULT_PERSIST_CACHE_DIR: &str = ".memoize-cache";
 
 #[derive(Debug, Clone)]
 pub struct MemoizeStats {
     pub function_name: &'static str,
     pub hits: u64,
     pub misses: u64,
+    /// Misses whose result was not stored because of `cache_if`.
+    pub skipped: u64,
+    /// Entries in the cache of the active scope. Outside of any scope, the
+    /// larger of the global cache and the cache of the most recently ended
+    /// scope.
     pub cache_size: usize,
+    pub loaded: u64,
+    pub loaded_hits: u64,
+    /// Time spent computing misses, excluding nested calls of the same
+    /// function.
+    pub compute_time: Duration,
+    /// Shallow estimate of the memory held by the cache: heap data owned by
+    /// keys or values (e.g. `String` contents) is not counted.
+    pub approx_bytes: usize,
+    /// Deepest nesting of computing calls on a single thread.
+    pub max_depth: u64,
 }
 
 impl MemoizeStats {
@@ -21,6 +52,37 @@ impl MemoizeStats {
     pub fn total_calls(&self) -> u64 {
         self.hits + self.misses
     }
+
+    pub fn average_compute_time(&self) -> Duration {
+        if self.misses == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos((self.compute_time.as_nanos() / self.misses as u128) as u64)
+        }
+    }
+
+    /// Estimates the time hits saved by assuming each one would have cost an
+    /// average miss.
+    pub fn estimated_time_saved(&self) -> Duration {
+        Duration::from_nanos((self.average_compute_time().as_nanos() * self.hits as u128) as u64)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "function_name": self.function_name,
+            "hits": self.hits,
+            "misses": self.misses,
+            "skipped": self.skipped,
+            "hit_rate": self.hit_rate(),
+            "cache_size": self.cache_size,
+            "loaded": self.loaded,
+            "loaded_hits": self.loaded_hits,
+            "compute_time_ns": self.compute_time.as_nanos() as u64,
+            "estimated_time_saved_ns": self.estimated_time_saved().as_nanos() as u64,
+            "approx_bytes": self.approx_bytes,
+            "max_depth": self.max_depth,
+        })
+    }
 }
 
 pub struct MemoizeStatsProvider {
@@ -28,10 +90,373 @@ pub struct MemoizeStatsProvider {
     pub get_stats: fn() -> MemoizeStats,
     pub clear_cache: fn(),
     pub reset_stats: fn(),
+    /// Only set for `#[memoize(persist)]` functions.
+    pub load_cache: Option<fn() -> u64>,
+    /// Only set for `#[memoize(persist)]` functions.
+    pub save_cache: Option<fn()>,
 }
 
 inventory::collect!(MemoizeStatsProvider);
 
+pub type SharedCache<K, V> = Arc<RwLock<HashMap<K, V>>>;
+
+struct ScopedCacheEntry {
+    cache: Arc<dyn Any + Send + Sync>,
+    len: fn(&(dyn Any + Send + Sync)) -> usize,
+    /// Where to record the cache size when the scope ends, if anywhere.
+    stats: Option<&'static AtomicStats>,
+}
+
+#[derive(Default)]
+struct ScopeCaches {
+    caches: RwLock<HashMap<TypeId, ScopedCacheEntry>>,
+    /// Where `#[memoize(persist)]` functions load and save their caches.
+    persist: Option<PersistContext>,
+}
+
+impl ScopeCaches {
+    fn record_cache_sizes(&self) {
+        for entry in self.caches.read().unwrap().values() {
+            if let Some(stats) = entry.stats {
+                stats.record_cache_size((entry.len)(&*entry.cache));
+            }
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT_SCOPE: RefCell<Option<Arc<ScopeCaches>>> = const { RefCell::new(None) };
+}
+
+/// Shared by every [`MemoScopeHandle::install`] call, created on first use.
+static POOL: OnceLock<rayon::ThreadPool> = OnceLock::new();
+/// The scope of the `install` call running on [`POOL`]. Workers that have
+/// not entered a scope themselves use it, so jobs stolen from a parallel
+/// iterator see the same caches as the closure that spawned them.
+static POOL_SCOPE: RwLock<Option<Arc<ScopeCaches>>> = RwLock::new(None);
+/// Held for the duration of an `install`, since the pool has one scope.
+static POOL_LOCK: Mutex<()> = Mutex::new(());
+
+/// The scope entered on this thread, or the pool's scope on a worker of
+/// [`POOL`].
+fn active_scope() -> Option<Arc<ScopeCaches>> {
+    CURRENT_SCOPE
+        .with(|scope| scope.borrow().clone())
+        .or_else(|| {
+            let on_pool = POOL
+                .get()
+                .is_some_and(|pool| pool.current_thread_index().is_some());
+            on_pool
+                .then(|| POOL_SCOPE.read().unwrap().clone())
+                .flatten()
+        })
+}
+
+/// Guard that isolates `#[memoize]` caches. While it is alive, memoized
+/// functions called on this thread use caches owned by the scope instead of
+/// the process-wide statics. The caches are dropped together with the last
+/// guard or handle of the scope.
+///
+/// Scopes are per thread. [`MemoScopeHandle::install`] runs a closure in a
+/// rayon pool whose workers all share the scope; other threads can join it
+/// with [`MemoScope::current`] and [`MemoScopeHandle::enter`].
+pub struct MemoScope {
+    previous: Option<Arc<ScopeCaches>>,
+    /// Set on the guard that created the scope, which records the final cache
+    /// sizes in the stats when it is dropped.
+    owned: Option<Arc<ScopeCaches>>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl MemoScope {
+    pub fn new() -> Self {
+        Self::open(ScopeCaches::default())
+    }
+
+    /// A fresh scope whose `#[memoize(persist)]` functions load from and save
+    /// to the caches of `context`.
+    pub fn persisted(context: PersistContext) -> Self {
+        Self::open(ScopeCaches {
+            persist: Some(context),
+            ..ScopeCaches::default()
+        })
+    }
+
+    fn open(scope: ScopeCaches) -> Self {
+        let scope = Arc::new(scope);
+        let mut guard = Self::enter(Some(scope.clone()));
+        guard.owned = Some(scope);
+        guard
+    }
+
+    /// Handle to the scope active on this thread, or to the global caches if
+    /// there is none.
+    pub fn current() -> MemoScopeHandle {
+        MemoScopeHandle(active_scope())
+    }
+
+    fn enter(scope: Option<Arc<ScopeCaches>>) -> Self {
+        let previous = CURRENT_SCOPE.with(|current| current.replace(scope));
+        Self {
+            previous,
+            owned: None,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Default for MemoScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for MemoScope {
+    fn drop(&mut self) {
+        if let Some(scope) = self.owned.take() {
+            scope.record_cache_sizes();
+        }
+        let previous = self.previous.take();
+        CURRENT_SCOPE.with(|current| current.replace(previous));
+    }
+}
+
+#[derive(Clone)]
+pub struct MemoScopeHandle(Option<Arc<ScopeCaches>>);
+
+impl MemoScopeHandle {
+    /// Makes this scope the active one on the calling thread until the
+    /// returned guard is dropped.
+    pub fn enter(&self) -> MemoScope {
+        MemoScope::enter(self.0.clone())
+    }
+
+    /// Runs `f` in a shared rayon pool whose worker threads all use this
+    /// scope, so parallel iterators inside `f` share its caches. The pool
+    /// serves one scope at a time: concurrent calls wait for each other, and
+    /// calls made from inside the pool just run `f` on the current worker.
+    pub fn install<R, F>(&self, f: F) -> R
+    where
+        R: Send,
+        F: FnOnce() -> R + Send,
+    {
+        let pool = POOL.get_or_init(|| {
+            rayon::ThreadPoolBuilder::new()
+                .thread_name(|index| format!("memoize-{}", index))
+                .build()
+                .expect("failed to build memoize thread pool")
+        });
+        if pool.current_thread_index().is_some() {
+            let _scope = self.enter();
+            return f();
+        }
+
+        let _lock = POOL_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let _pool_scope = PoolScope::set(self.0.clone());
+        pool.install(f)
+    }
+}
+
+/// Resets [`POOL_SCOPE`] when an `install` ends, even by panicking.
+struct PoolScope;
+
+impl PoolScope {
+    fn set(scope: Option<Arc<ScopeCaches>>) -> Self {
+        *POOL_SCOPE.write().unwrap() = scope;
+        Self
+    }
+}
+
+impl Drop for PoolScope {
+    fn drop(&mut self) {
+        *POOL_SCOPE.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+/// Returns the cache identified by `Marker` in the current scope, creating it
+/// on first use, or `None` outside of any scope. Its size is recorded in
+/// `stats` when the scope ends.
+pub fn scoped_cache<Marker, K, V>(stats: Option<&'static AtomicStats>) -> Option<SharedCache<K, V>>
+where
+    Marker: 'static,
+    K: Eq + Hash + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    let scope = active_scope()?;
+    let id = TypeId::of::<Marker>();
+
+    if let Some(entry) = scope.caches.read().unwrap().get(&id) {
+        return entry.cache.clone().downcast().ok();
+    }
+
+    let mut caches = scope.caches.write().unwrap();
+    let entry = caches.entry(id).or_insert_with(|| ScopedCacheEntry {
+        cache: SharedCache::<K, V>::default(),
+        len: cache_len::<K, V>,
+        stats,
+    });
+    entry.cache.clone().downcast().ok()
+}
+
+fn cache_len<K: 'static, V: 'static>(cache: &(dyn Any + Send + Sync)) -> usize {
+    cache
+        .downcast_ref::<RwLock<HashMap<K, V>>>()
+        .map_or(0, |cache| cache.read().unwrap().len())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistContext {
+    pub dir: PathBuf,
+    pub year: u16,
+    pub day: u8,
+    pub input_hash: u64,
+}
+
+impl PersistContext {
+    /// Context for a puzzle input, stored under [`DEFAULT_PERSIST_CACHE_DIR`].
+    pub fn new(year: u16, day: u8, input: &str) -> Self {
+        Self {
+            dir: PathBuf::from(DEFAULT_PERSIST_CACHE_DIR),
+            year,
+            day,
+            input_hash: input_hash(input),
+        }
+    }
+
+    /// The same context stored under `dir` instead.
+    pub fn in_dir(self, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ..self
+        }
+    }
+
+    /// File holding the persisted cache of `function_name`.
+    pub fn path(&self, function_name: &str) -> PathBuf {
+        self.dir
+            .join(self.year.to_string())
+            .join(format!("day{}", self.day))
+            .join(format!("{}-{:016x}.json", function_name, self.input_hash))
+    }
+}
+
+static PERSIST_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Sets the directory `run_day` persists caches in, or turns persistence off
+/// with `None`. Without a directory, `#[memoize(persist)]` functions behave
+/// like plain `#[memoize]` ones.
+pub fn set_persist_dir(dir: Option<PathBuf>) {
+    *PERSIST_DIR.write().unwrap() = dir;
+}
+
+pub fn persist_dir() -> Option<PathBuf> {
+    PERSIST_DIR.read().unwrap().clone()
+}
+
+/// The persist context of the active scope, if it was opened with
+/// [`MemoScope::persisted`].
+pub fn persist_context() -> Option<PersistContext> {
+    active_scope()?.persist.clone()
+}
+
+/// FNV-1a, so cache file names stay stable across Rust versions.
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn persist_path(function_name: &str) -> Option<PathBuf> {
+    Some(persist_context()?.path(function_name))
+}
+
+pub fn load_cache_entries<K, V>(function_name: &str) -> Option<Vec<(K, V)>>
+where
+    K: DeserializeOwned,
+    V: DeserializeOwned,
+{
+    let path = persist_path(function_name)?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn save_cache_entries<'a, K, V, I>(function_name: &str, entries: I)
+where
+    K: Serialize + 'a,
+    V: Serialize + 'a,
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+{
+    let Some(path) = persist_path(function_name) else {
+        return;
+    };
+    let entries: Vec<_> = entries.into_iter().collect();
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+            fs::write(&path, json)
+        });
+    if let Err(err) = result {
+        eprintln!("Failed to persist cache {}: {}", path.display(), err);
+    }
+}
+
+/// Loads the persisted entries of every `#[memoize(persist)]` function for the
+/// [`PersistContext`] of the active scope. Returns the number of entries loaded.
+pub fn load_persisted_caches() -> u64 {
+    inventory::iter::<MemoizeStatsProvider>
+        .into_iter()
+        .filter_map(|provider| provider.load_cache)
+        .map(|load| load())
+        .sum()
+}
+
+/// Saves the cache of every `#[memoize(persist)]` function that has entries in
+/// the active scope.
+pub fn save_persisted_caches() {
+    for provider in inventory::iter::<MemoizeStatsProvider> {
+        if let Some(save) = provider.save_cache {
+            save();
+        }
+    }
+}
+
+/// Removes persisted cache files under `dir`, optionally limited to a year and
+/// day. Returns the number of files removed.
+pub fn clear_persisted_caches(
+    dir: &Path,
+    year: Option<u16>,
+    day: Option<u8>,
+) -> std::io::Result<usize> {
+    let mut dir = dir.to_path_buf();
+    if let Some(year) = year {
+        dir.push(year.to_string());
+        if let Some(day) = day {
+            dir.push(format!("day{}", day));
+        }
+    }
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let removed = count_files(&dir)?;
+    fs::remove_dir_all(&dir)?;
+    Ok(removed)
+}
+
+fn count_files(dir: &Path) -> std::io::Result<usize> {
+    let mut count = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            count += count_files(&path)?;
+        } else {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
 pub fn clear_all_caches_and_stats() {
     for provider in inventory::iter::<MemoizeStatsProvider> {
         (provider.clear_cache)();
@@ -47,6 +472,21 @@ pub fn collect_used_stats() -> Vec<MemoizeStats> {
         .collect()
 }
 
+/// Serializes the stats of every used memoized function as a single-line JSON
+/// object, so runs of different days and strategies can be compared.
+pub fn memoize_stats_json(year: u16, day: u8, part: u8) -> String {
+    serde_json::json!({
+        "year": year,
+        "day": day,
+        "part": part,
+        "stats": collect_used_stats()
+            .iter()
+            .map(MemoizeStats::to_json)
+            .collect::<Vec<_>>(),
+    })
+    .to_string()
+}
+
 pub fn print_memoize_stats() {
     let stats = collect_used_stats();
     if stats.is_empty() {
@@ -67,6 +507,29 @@ pub fn print_memoize_stats() {
             format!("{:.1}%", stat.hit_rate()).blue(),
             format_number(stat.cache_size as u64).dimmed()
         );
+        if stat.skipped > 0 {
+            println!(
+                "   {} {} results not cached by cache_if",
+                "↳".dimmed(),
+                format_number(stat.skipped).yellow()
+            );
+        }
+        println!(
+            "   {} ~{} saved │ {} computing │ ~{} │ depth {}",
+            "↳".dimmed(),
+            format_duration(stat.estimated_time_saved()).green(),
+            format_duration(stat.compute_time).yellow(),
+            format_bytes(stat.approx_bytes).dimmed(),
+            format_number(stat.max_depth).dimmed()
+        );
+        if stat.loaded > 0 {
+            println!(
+                "   {} {} loaded from disk │ {} hits on loaded entries",
+                "↳".dimmed(),
+                format_number(stat.loaded).magenta(),
+                format_number(stat.loaded_hits).magenta()
+            );
+        }
     }
     println!("{}", "─".repeat(60).dimmed());
 }
@@ -83,9 +546,82 @@ fn format_number(n: u64) -> String {
     result
 }
 
+/// Formats a duration with a unit suited to its size, e.g. `1.50ms`.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1_000.0)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1_000_000.0)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1_000_000_000.0)
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    if bytes < 1 << 10 {
+        format!("{}B", bytes)
+    } else if bytes < 1 << 20 {
+        format!("{:.1}KiB", bytes as f64 / (1 << 10) as f64)
+    } else if bytes < 1 << 30 {
+        format!("{:.1}MiB", bytes as f64 / (1 << 20) as f64)
+    } else {
+        format!("{:.1}GiB", bytes as f64 / (1 << 30) as f64)
+    }
+}
+
+/// Per-thread bookkeeping of the computing calls of one memoized function.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallState {
+    depth: u64,
+    nested_nanos: u64,
+}
+
+impl CallState {
+    pub const fn new() -> Self {
+        Self {
+            depth: 0,
+            nested_nanos: 0,
+        }
+    }
+}
+
+/// Measures one computing call, created by [`AtomicStats::enter_call`]. On
+/// drop it records the time spent in the call itself, excluding nested calls
+/// of the same function, so recursive functions are not counted twice.
+pub struct CallGuard {
+    stats: &'static AtomicStats,
+    state: &'static LocalKey<Cell<CallState>>,
+    start: Instant,
+    outer_nested_nanos: u64,
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed().as_nanos() as u64;
+        let state = self.state.get();
+        self.stats.compute_nanos.fetch_add(
+            elapsed.saturating_sub(state.nested_nanos),
+            Ordering::Relaxed,
+        );
+        self.state.set(CallState {
+            depth: state.depth - 1,
+            nested_nanos: self.outer_nested_nanos + elapsed,
+        });
+    }
+}
+
 pub struct AtomicStats {
     pub hits: AtomicU64,
     pub misses: AtomicU64,
+    pub skipped: AtomicU64,
+    pub loaded: AtomicU64,
+    pub loaded_hits: AtomicU64,
+    pub last_scope_cache_size: AtomicUsize,
+    pub compute_nanos: AtomicU64,
+    pub max_depth: AtomicU64,
 }
 
 impl AtomicStats {
@@ -93,6 +629,12 @@ impl AtomicStats {
         Self {
             hits: AtomicU64::new(0),
             misses: AtomicU64::new(0),
+            skipped: AtomicU64::new(0),
+            loaded: AtomicU64::new(0),
+            loaded_hits: AtomicU64::new(0),
+            last_scope_cache_size: AtomicUsize::new(0),
+            compute_nanos: AtomicU64::new(0),
+            max_depth: AtomicU64::new(0),
         }
     }
 
@@ -104,6 +646,39 @@ impl AtomicStats {
         self.misses.fetch_add(1, Ordering::Relaxed);
     }
 
+    pub fn record_skipped(&self) {
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn enter_call(&'static self, state: &'static LocalKey<Cell<CallState>>) -> CallGuard {
+        let outer = state.get();
+        state.set(CallState {
+            depth: outer.depth + 1,
+            nested_nanos: 0,
+        });
+        self.max_depth.fetch_max(outer.depth + 1, Ordering::Relaxed);
+        CallGuard {
+            stats: self,
+            state,
+            start: Instant::now(),
+            outer_nested_nanos: outer.nested_nanos,
+        }
+    }
+
+    pub fn record_loaded(&self, count: u64) {
+        self.loaded.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_loaded_hit(&self) {
+        self.loaded_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the size of a scoped cache when its scope ends, replacing the
+    /// size recorded for the previous scope.
+    pub fn record_cache_size(&self, size: usize) {
+        self.last_scope_cache_size.store(size, Ordering::Relaxed);
+    }
+
     pub fn get_hits(&self) -> u64 {
         self.hits.load(Ordering::Relaxed)
     }
@@ -112,8 +687,285 @@ impl AtomicStats {
         self.misses.load(Ordering::Relaxed)
     }
 
+    pub fn get_skipped(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    pub fn get_loaded(&self) -> u64 {
+        self.loaded.load(Ordering::Relaxed)
+    }
+
+    pub fn get_loaded_hits(&self) -> u64 {
+        self.loaded_hits.load(Ordering::Relaxed)
+    }
+
+    pub fn get_last_scope_cache_size(&self) -> usize {
+        self.last_scope_cache_size.load(Ordering::Relaxed)
+    }
+
+    pub fn get_compute_time(&self) -> Duration {
+        Duration::from_nanos(self.compute_nanos.load(Ordering::Relaxed))
+    }
+
+    pub fn get_max_depth(&self) -> u64 {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
     pub fn reset(&self) {
         self.hits.store(0, Ordering::Relaxed);
         self.misses.store(0, Ordering::Relaxed);
+        self.skipped.store(0, Ordering::Relaxed);
+        self.loaded.store(0, Ordering::Relaxed);
+        self.loaded_hits.store(0, Ordering::Relaxed);
+        self.last_scope_cache_size.store(0, Ordering::Relaxed);
+        self.compute_nanos.store(0, Ordering::Relaxed);
+        self.max_depth.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_derive::memoize;
+    use std::sync::atomic::AtomicI64;
+
+    static OFFSET: AtomicI64 = AtomicI64::new(0);
+
+    #[memoize]
+    fn offset_by(n: i64) -> i64 {
+        n + OFFSET.load(Ordering::Relaxed)
+    }
+
+    #[test]
+    fn test_scopes_are_isolated() {
+        {
+            let _scope = MemoScope::new();
+            OFFSET.store(10, Ordering::Relaxed);
+            assert_eq!(offset_by(1), 11);
+            assert_eq!(offset_by_cache::stats().cache_size, 1);
+        }
+
+        let _scope = MemoScope::new();
+        OFFSET.store(20, Ordering::Relaxed);
+        assert_eq!(offset_by(1), 21);
+
+        let handle = MemoScope::current();
+        std::thread::spawn(move || {
+            let _scope = handle.enter();
+            OFFSET.store(30, Ordering::Relaxed);
+            assert_eq!(offset_by(1), 21);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[memoize]
+    fn triple(n: u64) -> u64 {
+        n * 3
+    }
+
+    #[test]
+    fn test_install_shares_scope_with_workers() {
+        use rayon::prelude::*;
+
+        for size in [100, 40] {
+            let _scope = MemoScope::new();
+            let sum =
+                MemoScope::current().install(|| (0..size).into_par_iter().map(triple).sum::<u64>());
+            assert_eq!(sum, 3 * size * (size - 1) / 2);
+            assert_eq!(triple_cache::stats().cache_size, size as usize);
+        }
+        assert!(triple_cache::CACHE.read().unwrap().is_empty());
+        assert_eq!(
+            triple_cache::stats().cache_size,
+            40,
+            "the size of the last scope, not the largest"
+        );
+    }
+
+    #[memoize]
+    fn quadruple(n: u64) -> u64 {
+        n * 4
+    }
+
+    #[test]
+    fn test_concurrent_installs_keep_their_scopes() {
+        use rayon::prelude::*;
+
+        let threads = [10, 25].map(|size| {
+            std::thread::spawn(move || {
+                let _scope = MemoScope::new();
+                for _ in 0..20 {
+                    MemoScope::current()
+                        .install(|| (0..size).into_par_iter().map(quadruple).sum::<u64>());
+                }
+                let len = quadruple_cache::current().read().unwrap().len();
+                len
+            })
+        });
+        let sizes = threads.map(|thread| thread.join().unwrap());
+        assert_eq!(sizes, [10, 25]);
+    }
+
+    #[memoize(cache_if = "some")]
+    fn checked_half(n: i64) -> Option<i64> {
+        (n % 2 == 0).then_some(n / 2)
+    }
+
+    #[memoize(key = (n), cache_if = |result: &i64| *result > 0)]
+    fn positive_offset(n: i64, offset: i64) -> i64 {
+        n + offset
+    }
+
+    #[test]
+    fn test_cache_if() {
+        let _scope = MemoScope::new();
+        assert_eq!(checked_half(3), None);
+        assert_eq!(checked_half(4), Some(2));
+        assert_eq!(checked_half_cache::current().read().unwrap().len(), 1);
+
+        assert_eq!(positive_offset(1, -5), -4);
+        assert_eq!(positive_offset(1, 5), 6);
+        assert_eq!(positive_offset(1, -5), 6);
+        assert_eq!(positive_offset_cache::current().read().unwrap().len(), 1);
+    }
+
+    #[memoize]
+    fn fibonacci(n: u64) -> u64 {
+        if n < 2 {
+            n
+        } else {
+            fibonacci(n - 1) + fibonacci(n - 2)
+        }
+    }
+
+    #[test]
+    fn test_call_stats() {
+        let _scope = MemoScope::new();
+        fibonacci_cache::reset_stats();
+        assert_eq!(fibonacci(40), 102_334_155);
+
+        let stats = fibonacci_cache::stats();
+        assert_eq!((stats.misses, stats.hits), (41, 38));
+        assert_eq!(stats.max_depth, 40, "fibonacci(40) down to fibonacci(1)");
+        assert!(stats.compute_time > Duration::ZERO);
+        assert_eq!(stats.approx_bytes, 41 * std::mem::size_of::<(u64, u64)>());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&memoize_stats_json(2023, 1, 2)).unwrap();
+        assert_eq!(
+            (json["year"].as_u64(), json["part"].as_u64()),
+            (Some(2023), Some(2))
+        );
+        let entry = json["stats"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["function_name"] == "fibonacci")
+            .unwrap();
+        for key in [
+            "hits",
+            "misses",
+            "cache_size",
+            "compute_time_ns",
+            "estimated_time_saved_ns",
+            "approx_bytes",
+            "max_depth",
+        ] {
+            assert!(entry.get(key).is_some(), "missing {}", key);
+        }
+        assert_eq!(entry["max_depth"], 40);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.50ms");
+        assert_eq!(format_duration(Duration::from_secs(2)), "2.00s");
+    }
+
+    #[test]
+    fn test_input_hash_is_stable() {
+        assert_eq!(input_hash(""), 0xcbf29ce484222325);
+        assert_ne!(input_hash("a"), input_hash("b"));
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("memoize-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[memoize(persist)]
+    fn square(n: u64) -> u64 {
+        n * n
+    }
+
+    #[test]
+    fn test_persist_round_trip() {
+        let dir = temp_dir("round-trip");
+        let context = PersistContext::new(2099, 1, "some input").in_dir(&dir);
+
+        {
+            let _scope = MemoScope::persisted(context.clone());
+            assert_eq!(load_persisted_caches(), 0);
+            assert_eq!((square(3), square(4)), (9, 16));
+            save_persisted_caches();
+        }
+        let path = dir
+            .join("2099")
+            .join("day1")
+            .join(format!("square-{:016x}.json", input_hash("some input")));
+        assert_eq!(context.path("square"), path);
+        assert!(path.exists());
+
+        let loaded_hits = square_cache::stats().loaded_hits;
+        {
+            let _scope = MemoScope::persisted(context.clone());
+            assert_eq!(load_persisted_caches(), 2);
+            assert_eq!(square(3), 9);
+            assert_eq!(square(5), 25);
+            assert_eq!(square_cache::stats().loaded_hits, loaded_hits + 1);
+        }
+        {
+            // Keys loaded into an earlier scope are not loaded in this one
+            let _scope = MemoScope::new();
+            assert_eq!(load_persisted_caches(), 0);
+            assert_eq!((square(3), square(3)), (9, 9));
+            assert_eq!(square_cache::stats().loaded_hits, loaded_hits + 1);
+        }
+        {
+            // Another input has caches of its own
+            let other = PersistContext::new(2099, 1, "other input").in_dir(&dir);
+            let _scope = MemoScope::persisted(other.clone());
+            assert_eq!(load_persisted_caches(), 0);
+            save_persisted_caches();
+            assert!(!other.path("square").exists(), "empty caches are not saved");
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_clear_persisted_caches() {
+        let dir = temp_dir("clear");
+        for (year, day, name) in [(2099, 1, "a"), (2099, 2, "b"), (2098, 1, "c")] {
+            let day_dir = dir.join(year.to_string()).join(format!("day{}", day));
+            fs::create_dir_all(&day_dir).unwrap();
+            fs::write(day_dir.join(format!("{}.json", name)), "[]").unwrap();
+        }
+
+        assert_eq!(
+            clear_persisted_caches(&dir, Some(2099), Some(1)).unwrap(),
+            1
+        );
+        assert!(dir.join("2099").join("day2").join("b.json").exists());
+        assert_eq!(
+            clear_persisted_caches(&dir, Some(2099), Some(1)).unwrap(),
+            0
+        );
+        assert_eq!(clear_persisted_caches(&dir, Some(2099), None).unwrap(), 1);
+        assert_eq!(clear_persisted_caches(&dir, None, None).unwrap(), 1);
+        assert!(!dir.exists());
     }
 }
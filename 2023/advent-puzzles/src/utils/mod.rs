@@ -1,8 +1,25 @@
+pub mod compress;
 pub mod counter;
+pub mod cycle;
+pub mod dag;
 pub mod direction;
+pub mod dp;
+pub mod dsu;
+pub mod graph;
+pub mod grid;
+pub mod grid_3d;
+pub mod hex;
+pub mod hyper_box;
+pub mod interval;
+pub mod junction;
 pub mod map;
 pub mod math;
 pub mod memoize;
 pub mod parsing;
 pub mod point;
 pub mod point_3d;
+pub mod polygon;
+pub mod region;
+pub mod search;
+pub mod sparse_grid;
+pub mod tile;
@@ -1,45 +1,51 @@
 use std::{
     fmt::Display,
-    i32,
-    ops::{Add, Mul},
+    hash::Hash,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
 };
 
 use array2d::Array2D;
-use i_overlay::i_float::float::compatible::FloatPointCompatible;
+use num::{PrimInt, Signed};
 
-use super::direction::Direction;
+use super::direction::{Direction, Direction8};
+
+/// Signed integer types usable as [`Point`] coordinates.
+pub trait Coordinate: PrimInt + Signed + Hash {}
+
+impl<T: PrimInt + Signed + Hash> Coordinate for T {}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Point {
-    pub x: i32,
-    pub y: i32,
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
 }
 
-impl Point {
-    pub fn new(x: i32, y: i32) -> Self {
+impl<T: Coordinate> Point<T> {
+    pub fn new(x: T, y: T) -> Self {
         Self { x, y }
     }
 
     pub fn from_index(index: usize, width: usize) -> Self {
         Self {
-            x: (index % width) as i32,
-            y: (index / width) as i32,
+            x: <T as num::NumCast>::from(index % width).expect("x should fit the coordinate type"),
+            y: <T as num::NumCast>::from(index / width).expect("y should fit the coordinate type"),
         }
     }
 
     pub fn index(&self, width: usize) -> usize {
-        (self.y as usize) * width + (self.x as usize)
+        (self.y.to_i64().unwrap() as usize) * width + (self.x.to_i64().unwrap() as usize)
     }
 
     pub fn ok(&self) -> Option<Self> {
-        if self.x >= 0 && self.y >= 0 {
+        if self.x >= T::zero() && self.y >= T::zero() {
             Some(*self)
         } else {
             None
         }
     }
 
-    pub fn ok_dimensions(&self, min_x: i32, min_y: i32, max_x: i32, max_y: i32) -> Option<Self> {
+    pub fn ok_dimensions(&self, min_x: T, min_y: T, max_x: T, max_y: T) -> Option<Self> {
         if self.x >= min_x && self.y >= min_y && self.x <= max_x && self.y <= max_y {
             Some(*self)
         } else {
@@ -47,36 +53,32 @@ impl Point {
         }
     }
 
-    pub fn ok_map<T>(&self, map: &Array2D<T>) -> Option<Self> {
-        self.ok_dimensions(
-            0,
-            0,
-            map.num_columns() as i32 - 1,
-            map.num_rows() as i32 - 1,
-        )
+    pub fn ok_map<A>(&self, map: &Array2D<A>) -> Option<Self> {
+        let (width, height) = map_dimensions::<T, A>(map)?;
+        self.ok_dimensions(T::zero(), T::zero(), width - T::one(), height - T::one())
     }
 
-    pub fn move_in_direction(&self, direction: Direction) -> Point {
-        self.move_n_in_direction(direction, 1)
+    pub fn move_in_direction(&self, direction: Direction) -> Self {
+        self.move_n_in_direction(direction, T::one())
     }
 
-    pub fn up(&self) -> Point {
+    pub fn up(&self) -> Self {
         self.move_in_direction(Direction::North)
     }
 
-    pub fn down(&self) -> Point {
+    pub fn down(&self) -> Self {
         self.move_in_direction(Direction::South)
     }
 
-    pub fn left(&self) -> Point {
+    pub fn left(&self) -> Self {
         self.move_in_direction(Direction::West)
     }
 
-    pub fn right(&self) -> Point {
+    pub fn right(&self) -> Self {
         self.move_in_direction(Direction::East)
     }
 
-    pub fn move_n_in_direction(&self, direction: Direction, n: i32) -> Point {
+    pub fn move_n_in_direction(&self, direction: Direction, n: T) -> Self {
         match direction {
             Direction::North => Self::new(self.x, self.y - n),
             Direction::East => Self::new(self.x + n, self.y),
@@ -85,7 +87,7 @@ impl Point {
         }
     }
 
-    pub fn neighbours(&self) -> Vec<Point> {
+    pub fn neighbours(&self) -> Vec<Self> {
         vec![
             self.move_in_direction(Direction::North),
             self.move_in_direction(Direction::East),
@@ -94,27 +96,38 @@ impl Point {
         ]
     }
 
-    pub fn neighbours_all_directions(&self) -> Vec<Point> {
+    /// Moves one step, diagonally for the intercardinal directions.
+    pub fn move_in_direction8(&self, direction: Direction8) -> Self {
+        let (dx, dy) = direction.delta();
+        let step =
+            |d: i32| <T as num::NumCast>::from(d).expect("-1, 0 and 1 fit every signed type");
+        Self::new(self.x + step(dx), self.y + step(dy))
+    }
+
+    /// The eight surrounding points, row by row from the top-left: NW, N, NE,
+    /// W, E, SW, S, SE. Iterate over [`Direction8`] for clockwise order.
+    pub fn neighbours_all_directions(&self) -> Vec<Self> {
+        let one = T::one();
         vec![
-            Self::new(self.x - 1, self.y - 1),
-            Self::new(self.x, self.y - 1),
-            Self::new(self.x + 1, self.y - 1),
-            Self::new(self.x - 1, self.y),
-            Self::new(self.x + 1, self.y),
-            Self::new(self.x - 1, self.y + 1),
-            Self::new(self.x, self.y + 1),
-            Self::new(self.x + 1, self.y + 1),
+            Self::new(self.x - one, self.y - one),
+            Self::new(self.x, self.y - one),
+            Self::new(self.x + one, self.y - one),
+            Self::new(self.x - one, self.y),
+            Self::new(self.x + one, self.y),
+            Self::new(self.x - one, self.y + one),
+            Self::new(self.x, self.y + one),
+            Self::new(self.x + one, self.y + one),
         ]
     }
 
-    pub fn neighbours_within_map<T>(&self, map: &Array2D<T>) -> Vec<Point> {
+    pub fn neighbours_within_map<A>(&self, map: &Array2D<A>) -> Vec<Self> {
         self.neighbours()
             .into_iter()
             .filter(|p| p.ok_map(map).is_some())
             .collect()
     }
 
-    pub fn neighbours_within_map_all_directions<T>(&self, map: &Array2D<T>) -> Vec<Point> {
+    pub fn neighbours_within_map_all_directions<A>(&self, map: &Array2D<A>) -> Vec<Self> {
         self.neighbours_all_directions()
             .into_iter()
             .filter(|p| p.ok_map(map).is_some())
@@ -123,11 +136,11 @@ impl Point {
 
     pub fn neighbours_within_dimensions(
         &self,
-        min_x: i32,
-        min_y: i32,
-        max_x: i32,
-        max_y: i32,
-    ) -> Vec<Point> {
+        min_x: T,
+        min_y: T,
+        max_x: T,
+        max_y: T,
+    ) -> Vec<Self> {
         self.neighbours()
             .into_iter()
             .filter(|p| p.ok_dimensions(min_x, min_y, max_x, max_y).is_some())
@@ -136,85 +149,153 @@ impl Point {
 
     pub fn neighbours_within_dimensions_all_directions(
         &self,
-        min_x: i32,
-        min_y: i32,
-        max_x: i32,
-        max_y: i32,
-    ) -> Vec<Point> {
+        min_x: T,
+        min_y: T,
+        max_x: T,
+        max_y: T,
+    ) -> Vec<Self> {
         self.neighbours_all_directions()
             .into_iter()
             .filter(|p| p.ok_dimensions(min_x, min_y, max_x, max_y).is_some())
             .collect()
     }
 
-    pub fn manhattan_distance(&self, other: &Point) -> usize {
-        ((self.x - other.x).abs() + (self.y - other.y).abs()) as usize
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        let distance = (self.x - other.x).abs() + (self.y - other.y).abs();
+        distance.to_usize().unwrap()
+    }
+
+    /// Distance when diagonal steps are allowed: the larger of the two axis
+    /// distances.
+    pub fn chebyshev_distance(&self, other: &Self) -> usize {
+        let distance = (self.x - other.x).abs().max((self.y - other.y).abs());
+        distance.to_usize().unwrap()
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z component of the 3D cross product. Positive when `other` is
+    /// counter-clockwise from `self` in a y-up coordinate system.
+    pub fn cross(&self, other: &Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Component-wise signum, turning any offset into a unit step.
+    pub fn signum(&self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(self.x.abs(), self.y.abs())
     }
 
     pub fn is_origin(&self) -> bool {
-        self.x == 0 && self.y == 0
+        self.x.is_zero() && self.y.is_zero()
+    }
+
+    pub fn looping_map<A>(&self, map: &Array2D<A>) -> Self {
+        let (width, height) =
+            map_dimensions::<T, A>(map).expect("map dimensions should fit the coordinate type");
+        Self::new(rem_euclid(self.x, width), rem_euclid(self.y, height))
+    }
+
+    /// Lossless conversion to a wider coordinate type.
+    pub fn convert<U: Coordinate + From<T>>(self) -> Point<U> {
+        Point::new(<U as From<T>>::from(self.x), <U as From<T>>::from(self.y))
+    }
+
+    /// Checked conversion to another coordinate type, failing if either
+    /// coordinate does not fit.
+    pub fn try_convert<U: Coordinate + TryFrom<T>>(self) -> Result<Point<U>, U::Error> {
+        Ok(Point::new(U::try_from(self.x)?, U::try_from(self.y)?))
     }
+}
+
+fn map_dimensions<T: Coordinate, A>(map: &Array2D<A>) -> Option<(T, T)> {
+    Some((T::from(map.num_columns())?, T::from(map.num_rows())?))
+}
 
-    pub fn looping_map<T>(&self, map: &Array2D<T>) -> Point {
-        let width = map.num_columns() as i32;
-        let height = map.num_rows() as i32;
-        Self::new(self.x.rem_euclid(width), self.y.rem_euclid(height))
+fn rem_euclid<T: Coordinate>(n: T, m: T) -> T {
+    let r = n % m;
+    if r < T::zero() {
+        r + m.abs()
+    } else {
+        r
     }
 }
 
-impl Display for Point {
+impl<T: Display> Display for Point<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
     }
 }
 
-impl From<(i32, i32)> for Point {
-    fn from((x, y): (i32, i32)) -> Self {
+impl<T: Coordinate> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
         Self::new(x, y)
     }
 }
 
-impl From<Point> for (i32, i32) {
-    fn from(point: Point) -> Self {
+impl<T> From<Point<T>> for (T, T) {
+    fn from(point: Point<T>) -> Self {
         (point.x, point.y)
     }
 }
 
-impl Ord for Point {
+impl<T: Ord> Ord for Point<T> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.y.cmp(&other.y).then_with(|| self.x.cmp(&other.x))
     }
 }
 
-impl PartialOrd for Point {
+impl<T: Ord> PartialOrd for Point<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Default for Point {
+impl<T: Coordinate> Default for Point<T> {
     fn default() -> Self {
-        Self::new(0, 0)
+        Self::new(T::zero(), T::zero())
     }
 }
 
-impl Mul<i32> for Point {
+impl<T: Coordinate> Mul<T> for Point<T> {
     type Output = Self;
 
-    fn mul(self, rhs: i32) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         Self::new(self.x * rhs, self.y * rhs)
     }
 }
 
-impl Mul<Point> for i32 {
-    type Output = Point;
+macro_rules! impl_scalar_mul {
+    ($($t:ty),*) => {
+        $(
+            impl Mul<Point<$t>> for $t {
+                type Output = Point<$t>;
+
+                fn mul(self, rhs: Point<$t>) -> Self::Output {
+                    Self::Output::new(self * rhs.x, self * rhs.y)
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_mul!(i8, i16, i32, i64, i128, isize);
 
-    fn mul(self, rhs: Point) -> Self::Output {
-        Self::Output::new(self * rhs.x, self * rhs.y)
+/// Truncating division of both coordinates.
+impl<T: Coordinate> Div<T> for Point<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Self::new(self.x / rhs, self.y / rhs)
     }
 }
 
-impl Add<Point> for Point {
+impl<T: Coordinate> Add<Point<T>> for Point<T> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -222,15 +303,49 @@ impl Add<Point> for Point {
     }
 }
 
-impl Add<(i32, i32)> for Point {
+impl<T: Coordinate> Add<(T, T)> for Point<T> {
     type Output = Self;
 
-    fn add(self, rhs: (i32, i32)) -> Self::Output {
+    fn add(self, rhs: (T, T)) -> Self::Output {
         Self::new(self.x + rhs.0, self.y + rhs.1)
     }
 }
 
-impl Add<Direction> for Point {
+impl<T: Coordinate> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coordinate> Sub<Point<T>> for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Coordinate> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Coordinate> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Coordinate> Sum for Point<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<T: Coordinate> Add<Direction> for Point<T> {
     type Output = Self;
 
     fn add(self, rhs: Direction) -> Self::Output {
@@ -238,24 +353,88 @@ impl Add<Direction> for Point {
     }
 }
 
-impl Add<Direction> for &Point {
-    type Output = Point;
+impl<T: Coordinate> Add<Direction> for &Point<T> {
+    type Output = Point<T>;
 
     fn add(self, rhs: Direction) -> Self::Output {
         self.move_in_direction(rhs)
     }
 }
 
-impl FloatPointCompatible<f32> for Point {
-    fn from_xy(x: f32, y: f32) -> Self {
-        Self::new(x as i32, y as i32)
-    }
+impl<T: Coordinate> Add<Direction8> for Point<T> {
+    type Output = Self;
 
-    fn x(&self) -> f32 {
-        self.x as f32
+    fn add(self, rhs: Direction8) -> Self::Output {
+        self.move_in_direction8(rhs)
     }
+}
 
-    fn y(&self) -> f32 {
-        self.y as f32
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_neighbour_order() {
+        let p = Point::new(5, 5);
+        let expected = [
+            Direction8::NorthWest,
+            Direction8::North,
+            Direction8::NorthEast,
+            Direction8::West,
+            Direction8::East,
+            Direction8::SouthWest,
+            Direction8::South,
+            Direction8::SouthEast,
+        ]
+        .map(|direction| p + direction);
+        assert_eq!(p.neighbours_all_directions(), expected);
+        assert_eq!(Direction8::iter().next(), Some(Direction8::North));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(3, -4);
+        let b = Point::new(1, 2);
+        assert_eq!(a + b, Point::new(4, -2));
+        assert_eq!(a - b, Point::new(2, -6));
+        assert_eq!(-a, Point::new(-3, 4));
+        assert_eq!(a * 2, Point::new(6, -8));
+        assert_eq!(2 * a, Point::new(6, -8));
+        assert_eq!(a / 2, Point::new(1, -2));
+
+        let mut c = a;
+        c += b;
+        c -= Point::new(0, 1);
+        assert_eq!(c, Point::new(4, -3));
+    }
+
+    #[test]
+    fn test_products_and_distances() {
+        let a = Point::<i64>::new(3, -4);
+        let b = Point::new(1, 2);
+        assert_eq!(a.dot(&b), -5);
+        assert_eq!(a.cross(&b), 10);
+        assert_eq!(a.signum(), Point::new(1, -1));
+        assert_eq!(a.abs(), Point::new(3, 4));
+        assert_eq!(a.manhattan_distance(&b), 8);
+        assert_eq!(a.chebyshev_distance(&b), 6);
+    }
+
+    #[test]
+    fn test_conversion() {
+        let wide: Point<i64> = Point::new(1, -2).convert();
+        assert_eq!(wide, Point::new(1i64, -2i64));
+        assert_eq!(wide.try_convert::<i8>(), Ok(Point::new(1, -2)));
+        assert!(Point::new(300i64, 0).try_convert::<i8>().is_err());
+    }
+
+    #[test]
+    fn test_map_helpers() {
+        let map = Array2D::filled_with('.', 2, 3);
+        assert_eq!(Point::new(2, 1).ok_map(&map), Some(Point::new(2, 1)));
+        assert_eq!(Point::new(3, 1).ok_map(&map), None);
+        assert_eq!(Point::new(-1, 2).looping_map(&map), Point::new(2, 0));
+        assert_eq!(Point::<i64>::new(-4, 5).looping_map(&map), Point::new(2, 1));
     }
 }
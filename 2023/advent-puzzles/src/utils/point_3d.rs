@@ -1,6 +1,12 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use num::{One, Zero};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct Point3D<T> {
     pub x: T,
     pub y: T,
@@ -28,4 +34,351 @@ where
         let dz = self.z - other.z;
         dx * dx + dy * dy + dz * dz
     }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+fn abs_diff<T: Copy + PartialOrd + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T> Point3D<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>,
+{
+    /// Works for unsigned coordinates as well, since it never produces a
+    /// negative intermediate.
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        let dx = abs_diff(self.x, other.x);
+        let dy = abs_diff(self.y, other.y);
+        let dz = abs_diff(self.z, other.z);
+        let max_xy = if dx > dy { dx } else { dy };
+        if max_xy > dz {
+            max_xy
+        } else {
+            dz
+        }
+    }
+
+    pub fn min(&self, other: &Self) -> Self {
+        let pick = |a: T, b: T| if a < b { a } else { b };
+        Self::new(
+            pick(self.x, other.x),
+            pick(self.y, other.y),
+            pick(self.z, other.z),
+        )
+    }
+
+    pub fn max(&self, other: &Self) -> Self {
+        let pick = |a: T, b: T| if a > b { a } else { b };
+        Self::new(
+            pick(self.x, other.x),
+            pick(self.y, other.y),
+            pick(self.z, other.z),
+        )
+    }
+}
+
+impl<T> Point3D<T>
+where
+    T: Copy + One + Add<Output = T> + Sub<Output = T>,
+{
+    /// The six face-adjacent neighbours. Unsigned coordinates underflow at 0,
+    /// use [`Grid3D`](super::grid_3d::Grid3D) for bounds-checked neighbours.
+    pub fn neighbours_6(&self) -> impl Iterator<Item = Self> {
+        let one = T::one();
+        let p = *self;
+        [
+            Self::new(p.x - one, p.y, p.z),
+            Self::new(p.x + one, p.y, p.z),
+            Self::new(p.x, p.y - one, p.z),
+            Self::new(p.x, p.y + one, p.z),
+            Self::new(p.x, p.y, p.z - one),
+            Self::new(p.x, p.y, p.z + one),
+        ]
+        .into_iter()
+    }
+
+    /// All 26 neighbours sharing a face, edge or corner.
+    pub fn neighbours_26(&self) -> impl Iterator<Item = Self> {
+        let p = *self;
+        let one = T::one();
+        let steps = [Step::Down, Step::Stay, Step::Up];
+        steps.into_iter().flat_map(move |dx| {
+            steps.into_iter().flat_map(move |dy| {
+                steps.into_iter().filter_map(move |dz| {
+                    if dx == Step::Stay && dy == Step::Stay && dz == Step::Stay {
+                        return None;
+                    }
+                    Some(Self::new(
+                        dx.apply(p.x, one),
+                        dy.apply(p.y, one),
+                        dz.apply(p.z, one),
+                    ))
+                })
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Down,
+    Stay,
+    Up,
+}
+
+impl Step {
+    fn apply<T: Add<Output = T> + Sub<Output = T>>(self, value: T, one: T) -> T {
+        match self {
+            Step::Down => value - one,
+            Step::Stay => value,
+            Step::Up => value + one,
+        }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3D<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Point3D<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3D<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Point3D<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3D<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point3D<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Copy + Div<Output = T>> Div<T> for Point3D<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Self::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl<T: Zero + Add<Output = T>> Sum for Point3D<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(T::zero(), T::zero(), T::zero()), Add::add)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3D<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T> From<Point3D<T>> for (T, T, T) {
+    fn from(point: Point3D<T>) -> Self {
+        (point.x, point.y, point.z)
+    }
+}
+
+impl<T> From<Point3D<T>> for [T; 3] {
+    fn from(point: Point3D<T>) -> Self {
+        [point.x, point.y, point.z]
+    }
+}
+
+/// Axis-aligned bounding box with inclusive corners, so a box with
+/// `min == max` holds exactly one cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aabb3D<T> {
+    pub min: Point3D<T>,
+    pub max: Point3D<T>,
+}
+
+impl<T> Aabb3D<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>,
+{
+    /// Creates the box spanned by two opposite corners in any order.
+    pub fn new(a: Point3D<T>, b: Point3D<T>) -> Self {
+        Self {
+            min: a.min(&b),
+            max: a.max(&b),
+        }
+    }
+
+    /// The smallest box containing all points, or `None` if there are none.
+    pub fn from_points<I: IntoIterator<Item = Point3D<T>>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Self::new(first, first);
+        points.for_each(|point| bounds.extend(point));
+        Some(bounds)
+    }
+
+    /// Grows the box to include `point`.
+    pub fn extend(&mut self, point: Point3D<T>) {
+        self.min = self.min.min(&point);
+        self.max = self.max.max(&point);
+    }
+
+    pub fn contains(&self, point: &Point3D<T>) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+            && self.min.z <= point.z
+            && point.z <= self.max.z
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.intersects(other).then(|| Self {
+            min: self.min.max(&other.min),
+            max: self.max.min(&other.max),
+        })
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(&other.min),
+            max: self.max.max(&other.max),
+        }
+    }
+
+    pub fn translate(&self, offset: Point3D<T>) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+}
+
+impl<T> Aabb3D<T>
+where
+    T: Copy + PartialOrd + One + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    /// Number of cells along each axis.
+    pub fn size(&self) -> Point3D<T> {
+        self.max - self.min + Point3D::new(T::one(), T::one(), T::one())
+    }
+
+    pub fn volume(&self) -> T {
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point3D::new(1, 2, 3);
+        let b = Point3D::new(4, -5, 6);
+        assert_eq!(a + b, Point3D::new(5, -3, 9));
+        assert_eq!(a - b, Point3D::new(-3, 7, -3));
+        assert_eq!(-a, Point3D::new(-1, -2, -3));
+        assert_eq!(a * 2, Point3D::new(2, 4, 6));
+        assert_eq!(b / 2, Point3D::new(2, -2, 3));
+        assert_eq!(a.dot(&b), 12);
+        assert_eq!(a.cross(&b), Point3D::new(27, 6, -13));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point3D::<u16>::new(1, 5, 3);
+        let b = Point3D::new(4, 2, 3);
+        assert_eq!(a.manhattan_distance(&b), 6);
+        assert_eq!(a.chebyshev_distance(&b), 3);
+        assert_eq!(a.distance_squared(&a), 0);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point3D::new(0, 0, 0);
+        assert_eq!(p.neighbours_6().count(), 6);
+        let all = p.neighbours_26().collect::<Vec<_>>();
+        assert_eq!(all.len(), 26);
+        assert!(!all.contains(&p));
+        assert!(all.contains(&Point3D::new(-1, 1, -1)));
+    }
+
+    #[test]
+    fn test_aabb() {
+        let a = Aabb3D::new(Point3D::new(2, 2, 2), Point3D::new(0, 0, 0));
+        let b = Aabb3D::new(Point3D::new(2, 2, 2), Point3D::new(3, 3, 3));
+        let c = Aabb3D::new(Point3D::new(3, 3, 3), Point3D::new(4, 4, 4));
+        assert_eq!(a.volume(), 27);
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Aabb3D::new(Point3D::new(2, 2, 2), Point3D::new(2, 2, 2)))
+        );
+        assert_eq!(a.union(&c).volume(), 125);
+        assert_eq!(
+            Aabb3D::from_points([Point3D::new(1, 5, 0), Point3D::new(3, 2, 1)]),
+            Some(Aabb3D::new(Point3D::new(1, 2, 0), Point3D::new(3, 5, 1)))
+        );
+    }
 }
@@ -0,0 +1,543 @@
+use super::{
+    direction::Direction,
+    point::{Coordinate, Point},
+};
+
+/// Winding direction of a polygon as seen on a grid where y grows downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    /// Zero area, e.g. all vertices on one line.
+    Degenerate,
+}
+
+/// Where a point lies relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Containment {
+    Inside,
+    OnBoundary,
+    Outside,
+}
+
+/// A simple polygon with integer vertices. The last vertex connects back to
+/// the first; collinear vertices are allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon<T = i32> {
+    vertices: Vec<Point<T>>,
+}
+
+impl<T: Coordinate> Polygon<T> {
+    /// A repeated first vertex at the end is dropped, so closed and open
+    /// vertex lists give the same polygon.
+    pub fn new(mut vertices: Vec<Point<T>>) -> Self {
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Self { vertices }
+    }
+
+    /// Walks the instructions from `start`, adding a vertex after each one.
+    pub fn from_instructions<I>(start: Point<T>, instructions: I) -> Self
+    where
+        I: IntoIterator<Item = (Direction, T)>,
+    {
+        let vertices = instructions
+            .into_iter()
+            .scan(start, |point, (direction, distance)| {
+                *point = point.move_n_in_direction(direction, distance);
+                Some(*point)
+            })
+            .collect();
+        Self::new(vertices)
+    }
+
+    pub fn vertices(&self) -> &[Point<T>] {
+        &self.vertices
+    }
+
+    /// Consecutive vertex pairs, including the closing edge.
+    pub fn edges(&self) -> impl Iterator<Item = (Point<T>, Point<T>)> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices.iter().copied().zip(next.copied())
+    }
+
+    /// Whether every edge is horizontal or vertical.
+    pub fn is_rectilinear(&self) -> bool {
+        self.edges().all(|(a, b)| a.x == b.x || a.y == b.y)
+    }
+
+    /// Twice the signed area by the shoelace formula. Positive for clockwise
+    /// polygons on a y-down grid.
+    pub fn signed_double_area(&self) -> T {
+        self.edges()
+            .fold(T::zero(), |area, (a, b)| area + a.cross(&b))
+    }
+
+    /// Twice the enclosed area, which is always an integer.
+    pub fn double_area(&self) -> T {
+        self.signed_double_area().abs()
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        let area = self.signed_double_area();
+        if area > T::zero() {
+            Orientation::Clockwise
+        } else if area < T::zero() {
+            Orientation::CounterClockwise
+        } else {
+            Orientation::Degenerate
+        }
+    }
+
+    /// Number of lattice points on the edges. For rectilinear polygons this is
+    /// also the perimeter.
+    pub fn boundary_points(&self) -> T {
+        self.edges().fold(T::zero(), |count, (a, b)| {
+            let offset = (b - a).abs();
+            count + gcd(offset.x, offset.y)
+        })
+    }
+
+    /// Number of lattice points strictly inside, by Pick's theorem.
+    pub fn interior_points(&self) -> T {
+        let two = T::one() + T::one();
+        (self.double_area() - self.boundary_points() + two) / two
+    }
+
+    /// Lattice points inside or on the boundary. On a grid where each point
+    /// is a cell, this is the number of cells covered by the polygon.
+    pub fn lattice_points(&self) -> T {
+        self.interior_points() + self.boundary_points()
+    }
+
+    /// Classifies `point` by casting a ray towards positive x. Exact for any
+    /// simple polygon, rectilinear or not.
+    pub fn contains(&self, point: &Point<T>) -> Containment {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let cross = (b - a).cross(&(*point - a));
+            let within = point.x >= a.x.min(b.x)
+                && point.x <= a.x.max(b.x)
+                && point.y >= a.y.min(b.y)
+                && point.y <= a.y.max(b.y);
+            if cross.is_zero() && within {
+                return Containment::OnBoundary;
+            }
+            // Half-open in y so a ray through a vertex counts it once.
+            if (a.y > point.y) != (b.y > point.y) {
+                let rises = b.y > a.y;
+                if (cross > T::zero()) == rises {
+                    inside = !inside;
+                }
+            }
+        }
+        if inside {
+            Containment::Inside
+        } else {
+            Containment::Outside
+        }
+    }
+}
+
+/// Axis-aligned rectangle spanning two corners, edges included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect<T = i32> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Coordinate> Rect<T> {
+    /// The rectangle with `a` and `b` as opposite corners, in any order.
+    pub fn from_corners(a: Point<T>, b: Point<T>) -> Self {
+        Self {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y
+    }
+
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    /// Lattice points covered, edges included, i.e. the number of grid cells
+    /// when both corners are cells.
+    pub fn lattice_points(&self) -> T {
+        (self.width() + T::one()) * (self.height() + T::one())
+    }
+
+    /// Clockwise on a y-down grid, from the top-left corner.
+    pub fn corners(&self) -> [Point<T>; 4] {
+        [
+            self.min,
+            Point::new(self.max.x, self.min.y),
+            self.max,
+            Point::new(self.min.x, self.max.y),
+        ]
+    }
+
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// The overlap of two rectangles, which may be a line or a single point
+    /// when they only touch.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Point::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        (min.x <= max.x && min.y <= max.y).then_some(Self { min, max })
+    }
+
+    /// The smallest rectangle containing both.
+    pub fn hull(&self, other: &Self) -> Self {
+        Self {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// Area covered by the union of `rects`, counting overlaps once. Sweeps
+    /// across x keeping how many rectangles cover each band between distinct
+    /// y coordinates, so it runs in `O(n²)` for `n` rectangles.
+    pub fn union_area(rects: &[Self]) -> T {
+        let mut ys = rects
+            .iter()
+            .flat_map(|r| [r.min.y, r.max.y])
+            .collect::<Vec<_>>();
+        ys.sort();
+        ys.dedup();
+        let mut events = rects
+            .iter()
+            .flat_map(|r| [(r.min.x, 1, r), (r.max.x, -1, r)])
+            .collect::<Vec<_>>();
+        events.sort_by_key(|&(x, _, _)| x);
+
+        let mut active = vec![0; ys.len().saturating_sub(1)];
+        let mut area = T::zero();
+        let mut previous_x = events.first().map_or(T::zero(), |&(x, _, _)| x);
+        for (x, delta, rect) in events {
+            let covered = ys
+                .windows(2)
+                .zip(&active)
+                .filter(|&(_, &count)| count > 0)
+                .fold(T::zero(), |length, (y, _)| length + y[1] - y[0]);
+            area = area + covered * (x - previous_x);
+            previous_x = x;
+
+            let band = |y| ys.binary_search(&y).expect("y should be a rectangle edge");
+            for count in &mut active[band(rect.min.y)..band(rect.max.y)] {
+                *count += delta;
+            }
+        }
+        area
+    }
+}
+
+/// A simple polygon whose edges are all horizontal or vertical, with edges
+/// indexed for fast exact containment queries. Areas are continuous: the
+/// polygon is the region enclosed by the lines through its vertices.
+#[derive(Debug, Clone)]
+pub struct RectilinearPolygon<T = i32> {
+    polygon: Polygon<T>,
+    /// `(x, min_y, max_y)`, sorted by x.
+    vertical: Vec<(T, T, T)>,
+    /// `(y, min_x, max_x)`, sorted by y.
+    horizontal: Vec<(T, T, T)>,
+}
+
+impl<T: Coordinate> RectilinearPolygon<T> {
+    /// Returns `None` if some edge is diagonal.
+    pub fn new(vertices: Vec<Point<T>>) -> Option<Self> {
+        let polygon = Polygon::new(vertices);
+        if !polygon.is_rectilinear() {
+            return None;
+        }
+        let mut vertical = Vec::new();
+        let mut horizontal = Vec::new();
+        for (a, b) in polygon.edges() {
+            if a.x == b.x {
+                vertical.push((a.x, a.y.min(b.y), a.y.max(b.y)));
+            } else {
+                horizontal.push((a.y, a.x.min(b.x), a.x.max(b.x)));
+            }
+        }
+        vertical.sort();
+        horizontal.sort();
+        Some(Self {
+            polygon,
+            vertical,
+            horizontal,
+        })
+    }
+
+    pub fn polygon(&self) -> &Polygon<T> {
+        &self.polygon
+    }
+
+    pub fn contains(&self, point: &Point<T>) -> Containment {
+        let two = T::one() + T::one();
+        self.classify_doubled(&Point::new(point.x * two, point.y * two))
+    }
+
+    /// Whether any edge passes through the open interior of `rect`. Edges
+    /// along the rectangle's border don't count.
+    pub fn crosses_interior(&self, rect: &Rect<T>) -> bool {
+        let crosses = |edges: &[(T, T, T)], low: T, high: T, from: T, to: T| {
+            let start = edges.partition_point(|&(at, _, _)| at <= low);
+            edges[start..]
+                .iter()
+                .take_while(|&&(at, _, _)| at < high)
+                .any(|&(_, min, max)| min < to && max > from)
+        };
+        crosses(
+            &self.vertical,
+            rect.min.x,
+            rect.max.x,
+            rect.min.y,
+            rect.max.y,
+        ) || crosses(
+            &self.horizontal,
+            rect.min.y,
+            rect.max.y,
+            rect.min.x,
+            rect.max.x,
+        )
+    }
+
+    /// Whether every point of `rect`, border included, is inside the polygon
+    /// or on its boundary.
+    pub fn contains_rect(&self, rect: &Rect<T>) -> bool {
+        if self.crosses_interior(rect) {
+            return false;
+        }
+        let two = T::one() + T::one();
+        // With nothing crossing it, the interior lies entirely on one side.
+        if rect.width() > T::zero() && rect.height() > T::zero() {
+            let centre = Point::new(rect.min.x + rect.max.x, rect.min.y + rect.max.y);
+            return self.classify_doubled(&centre) != Containment::Outside;
+        }
+
+        // A line or point: check every vertex coordinate along it and the
+        // midpoints between them, so no gap outside can be missed.
+        let (edges, fixed, from, to, horizontal) = if rect.height().is_zero() {
+            (&self.vertical, rect.min.y, rect.min.x, rect.max.x, true)
+        } else {
+            (&self.horizontal, rect.min.x, rect.min.y, rect.max.y, false)
+        };
+        let mut stops = edges
+            .iter()
+            .map(|&(at, _, _)| at)
+            .filter(|&at| from < at && at < to)
+            .chain([from, to])
+            .map(|at| at * two)
+            .collect::<Vec<_>>();
+        stops.sort();
+        stops.dedup();
+        let midpoints = stops
+            .windows(2)
+            .map(|pair| (pair[0] + pair[1]) / two)
+            .collect::<Vec<_>>();
+        stops.into_iter().chain(midpoints).all(|at| {
+            let point = if horizontal {
+                Point::new(at, fixed * two)
+            } else {
+                Point::new(fixed * two, at)
+            };
+            self.classify_doubled(&point) != Containment::Outside
+        })
+    }
+
+    /// Ray cast towards positive x with the polygon scaled by two, so rect
+    /// centres and midpoints stay on integer coordinates.
+    fn classify_doubled(&self, point: &Point<T>) -> Containment {
+        let two = T::one() + T::one();
+        let on_horizontal = self.horizontal.iter().any(|&(y, min, max)| {
+            point.y == y * two && min * two <= point.x && point.x <= max * two
+        });
+        let mut inside = false;
+        for &(x, min, max) in &self.vertical {
+            let (x, min, max) = (x * two, min * two, max * two);
+            if point.x == x && min <= point.y && point.y <= max {
+                return Containment::OnBoundary;
+            }
+            if x > point.x && min <= point.y && point.y < max {
+                inside = !inside;
+            }
+        }
+        if on_horizontal {
+            Containment::OnBoundary
+        } else if inside {
+            Containment::Inside
+        } else {
+            Containment::Outside
+        }
+    }
+}
+
+fn gcd<T: Coordinate>(mut a: T, mut b: T) -> T {
+    while !b.is_zero() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dig_plan() {
+        // 2023 day 18 example
+        let plan = "R6 D5 L2 D2 R2 D2 L5 U2 L1 U2 R2 U3 L2 U2";
+        let instructions = plan.split(' ').map(|step| {
+            let direction = step[..1].parse::<Direction>().unwrap();
+            (direction, step[1..].parse::<i64>().unwrap())
+        });
+        let lagoon = Polygon::from_instructions(Point::new(0, 0), instructions);
+        assert_eq!(lagoon.vertices().len(), 14);
+        assert!(lagoon.is_rectilinear());
+        assert_eq!(lagoon.orientation(), Orientation::Clockwise);
+        assert_eq!(lagoon.boundary_points(), 38);
+        assert_eq!(lagoon.double_area(), 84);
+        assert_eq!(lagoon.interior_points(), 24);
+        assert_eq!(lagoon.lattice_points(), 62);
+    }
+
+    #[test]
+    fn test_general_polygon() {
+        let triangle = Polygon::new(vec![
+            Point::new(0, 0),
+            Point::new(0, 4),
+            Point::new(4, 0),
+            Point::new(0, 0),
+        ]);
+        assert_eq!(triangle.vertices().len(), 3);
+        assert_eq!(triangle.orientation(), Orientation::CounterClockwise);
+        assert_eq!(triangle.double_area(), 16);
+        assert_eq!(triangle.boundary_points(), 12);
+        assert_eq!(triangle.interior_points(), 3);
+
+        assert_eq!(triangle.contains(&Point::new(1, 1)), Containment::Inside);
+        assert_eq!(
+            triangle.contains(&Point::new(2, 2)),
+            Containment::OnBoundary
+        );
+        assert_eq!(
+            triangle.contains(&Point::new(0, 3)),
+            Containment::OnBoundary
+        );
+        assert_eq!(triangle.contains(&Point::new(3, 3)), Containment::Outside);
+        assert_eq!(triangle.contains(&Point::new(-1, 0)), Containment::Outside);
+    }
+
+    #[test]
+    fn test_rectilinear_contains() {
+        // A U shape, so rays pass through the notch and along edges
+        let u = Polygon::new(vec![
+            Point::new(0, 0),
+            Point::new(2, 0),
+            Point::new(2, 3),
+            Point::new(4, 3),
+            Point::new(4, 0),
+            Point::new(6, 0),
+            Point::new(6, 5),
+            Point::new(0, 5),
+        ]);
+        assert_eq!(u.lattice_points(), 42 - 3);
+        assert_eq!(u.contains(&Point::new(1, 1)), Containment::Inside);
+        assert_eq!(u.contains(&Point::new(3, 1)), Containment::Outside);
+        assert_eq!(u.contains(&Point::new(3, 4)), Containment::Inside);
+        assert_eq!(u.contains(&Point::new(3, 3)), Containment::OnBoundary);
+        assert_eq!(u.contains(&Point::new(-1, 3)), Containment::Outside);
+        assert_eq!(u.contains(&Point::new(5, 0)), Containment::OnBoundary);
+        assert_eq!(u.contains(&Point::new(7, 0)), Containment::Outside);
+
+        let indexed = RectilinearPolygon::new(u.vertices().to_vec()).unwrap();
+        for x in -1..=7 {
+            for y in -1..=6 {
+                let point = Point::new(x, y);
+                assert_eq!(indexed.contains(&point), u.contains(&point), "{}", point);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rects() {
+        let a = Rect::from_corners(Point::new(4, 0), Point::new(0, 3));
+        let b = Rect::from_corners(Point::new(2, 1), Point::new(6, 5));
+        assert_eq!((a.width(), a.height(), a.area()), (4, 3, 12));
+        assert_eq!(a.lattice_points(), 20);
+        assert_eq!(a.corners()[1], Point::new(4, 0));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::from_corners(Point::new(2, 1), Point::new(4, 3)))
+        );
+        assert_eq!(
+            a.hull(&b),
+            Rect::from_corners(Point::new(0, 0), Point::new(6, 5))
+        );
+        assert_eq!(Rect::union_area(&[a, b]), 12 + 16 - 4);
+        let rect = |a: (i32, i32), b: (i32, i32)| {
+            Rect::from_corners(Point::new(a.0, a.1), Point::new(b.0, b.1))
+        };
+        let rects = [
+            rect((0, 0), (10, 10)),
+            rect((2, 2), (4, 4)),
+            rect((20, 0), (21, 1)),
+            rect((5, 5), (15, 7)),
+            rect((0, 0), (0, 5)),
+        ];
+        assert_eq!(Rect::union_area(&rects), 100 + 1 + 10);
+        assert_eq!(Rect::<i32>::union_area(&[]), 0);
+        let touching = Rect::from_corners(Point::new(4, 3), Point::new(9, 9));
+        assert_eq!(a.intersection(&touching).map(|r| r.area()), Some(0));
+        assert!(a.contains(&Point::new(4, 3)) && !a.contains(&Point::new(5, 3)));
+    }
+
+    #[test]
+    fn test_rect_in_polygon() {
+        // 2025 day 9 example
+        let tiles = [
+            (7, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ];
+        let vertices = tiles.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        let polygon = RectilinearPolygon::<i64>::new(vertices).unwrap();
+
+        let rect = |a: (i64, i64), b: (i64, i64)| {
+            Rect::from_corners(Point::new(a.0, a.1), Point::new(b.0, b.1))
+        };
+        assert!(polygon.contains_rect(&rect((9, 5), (2, 3))));
+        assert!(polygon.contains_rect(&rect((7, 3), (11, 1))));
+        assert!(!polygon.contains_rect(&rect((2, 5), (11, 1))));
+        assert!(polygon.crosses_interior(&rect((2, 5), (11, 1))));
+        // Lines along edges, through the interior and out of the polygon
+        assert!(polygon.contains_rect(&rect((2, 5), (9, 5))));
+        assert!(polygon.contains_rect(&rect((2, 4), (11, 4))));
+        assert!(!polygon.contains_rect(&rect((2, 3), (2, 1))));
+        assert!(!polygon.contains_rect(&rect((3, 6), (10, 6))));
+        assert!(!polygon.contains_rect(&rect((2, 1), (2, 1))));
+
+        let largest = tiles
+            .iter()
+            .flat_map(|&a| tiles.iter().map(move |&b| rect(a, b)))
+            .filter(|r| polygon.contains_rect(r))
+            .map(|r| r.lattice_points())
+            .max();
+        assert_eq!(largest, Some(24));
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+
+use strum::IntoEnumIterator;
+
+use super::{
+    direction::Direction,
+    map::{Dimensions, InteractWithPoint},
+    point::Point,
+};
+
+/// Which cells count as adjacent when growing a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// Orthogonal neighbours only.
+    Four,
+    /// Orthogonal and diagonal neighbours.
+    Eight,
+}
+
+impl Connectivity {
+    /// The in-bounds neighbours of `point` under this connectivity.
+    pub fn neighbours<M: InteractWithPoint>(self, map: &M, point: &Point) -> Vec<Point> {
+        match self {
+            Connectivity::Four => map.four_neighbours(point),
+            Connectivity::Eight => map.eight_neighbours(point),
+        }
+    }
+}
+
+/// Every point reachable from `seeds` through cells for which `passable`
+/// returns `true`, in the order they were reached. Seeds that are out of
+/// bounds or not passable are skipped.
+pub fn flood_fill<M, S, F>(
+    map: &M,
+    seeds: S,
+    connectivity: Connectivity,
+    mut passable: F,
+) -> Vec<Point>
+where
+    M: InteractWithPoint + Dimensions,
+    S: IntoIterator<Item = Point>,
+    F: FnMut(&Point, &M::Item) -> bool,
+{
+    let (width, height) = map.dimensions();
+    let mut visited = vec![false; width * height];
+    let mut try_visit = |point: &Point| {
+        let Some(item) = map.get_point(point) else {
+            return false;
+        };
+        let index = point.index(width);
+        if visited[index] || !passable(point, item) {
+            return false;
+        }
+        visited[index] = true;
+        true
+    };
+
+    let mut filled = seeds
+        .into_iter()
+        .filter(|seed| try_visit(seed))
+        .collect::<Vec<_>>();
+    let mut next = 0;
+    while let Some(&point) = filled.get(next) {
+        next += 1;
+        for neighbour in connectivity.neighbours(map, &point) {
+            if try_visit(&neighbour) {
+                filled.push(neighbour);
+            }
+        }
+    }
+    filled
+}
+
+/// A connected set of cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    points: HashSet<Point>,
+}
+
+impl Region {
+    pub fn new(points: HashSet<Point>) -> Self {
+        Self { points }
+    }
+
+    pub fn points(&self) -> &HashSet<Point> {
+        &self.points
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.points.contains(point)
+    }
+
+    pub fn area(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Number of cell edges between the region and anything outside it.
+    pub fn perimeter(&self) -> usize {
+        self.points
+            .iter()
+            .map(|point| {
+                Direction::iter()
+                    .filter(|direction| !self.contains(&(*point + *direction)))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of straight boundary segments, including those of holes. This
+    /// always equals the number of corners, which is what gets counted.
+    pub fn sides(&self) -> usize {
+        self.corners()
+    }
+
+    /// Convex and concave corners of the region's outline.
+    pub fn corners(&self) -> usize {
+        self.points
+            .iter()
+            .map(|point| {
+                Direction::iter()
+                    .filter(|&direction| {
+                        let turned = direction.turn_right();
+                        let straight = self.contains(&(*point + direction));
+                        let side = self.contains(&(*point + turned));
+                        let diagonal = self.contains(&(*point + direction + turned));
+                        (!straight && !side) || (straight && side && !diagonal)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// The smallest and largest corner of the region's bounding box.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let mut points = self.points.iter();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+}
+
+/// All connected components of a map, with a label per labelled cell.
+#[derive(Debug, Clone)]
+pub struct Components {
+    pub regions: Vec<Region>,
+    labels: Vec<Option<usize>>,
+    width: usize,
+}
+
+impl Components {
+    /// Index into `regions` of the component containing `point`.
+    pub fn label(&self, point: &Point) -> Option<usize> {
+        let in_bounds = point.x >= 0 && (point.x as usize) < self.width && point.y >= 0;
+        if !in_bounds {
+            return None;
+        }
+        *self.labels.get(point.index(self.width))?
+    }
+
+    pub fn region_of(&self, point: &Point) -> Option<&Region> {
+        self.label(point).map(|label| &self.regions[label])
+    }
+}
+
+/// Labels the connected components of a map. `key` decides which region a
+/// cell belongs to: neighbours with equal keys are joined, and cells with a
+/// `None` key are left out. Regions are numbered in row-major order of their
+/// first cell.
+pub fn connected_components<M, K, F>(map: &M, connectivity: Connectivity, mut key: F) -> Components
+where
+    M: InteractWithPoint + Dimensions,
+    K: PartialEq,
+    F: FnMut(&Point, &M::Item) -> Option<K>,
+{
+    let (width, height) = map.dimensions();
+    let keys = (0..width * height)
+        .map(|index| {
+            let point = Point::from_index(index, width);
+            map.get_point(&point).and_then(|item| key(&point, item))
+        })
+        .collect::<Vec<_>>();
+
+    let mut regions = Vec::new();
+    let mut labels = vec![None; keys.len()];
+    for (index, start_key) in keys.iter().enumerate() {
+        if start_key.is_none() || labels[index].is_some() {
+            continue;
+        }
+        let label = regions.len();
+        let start = Point::from_index(index, width);
+        let points = flood_fill(map, [start], connectivity, |point, _| {
+            &keys[point.index(width)] == start_key
+        });
+        for point in &points {
+            labels[point.index(width)] = Some(label);
+        }
+        regions.push(Region::new(points.into_iter().collect()));
+    }
+
+    Components {
+        regions,
+        labels,
+        width,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use array2d::Array2D;
+
+    use super::*;
+    use crate::utils::{grid::Grid, map::GenerateMap};
+
+    const GARDEN: &str = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+
+    #[test]
+    fn test_flood_fill() {
+        let map = Array2D::generate_map("..#\n.#.\n#..", |_, c| c).unwrap();
+        let four = flood_fill(&map, [Point::new(0, 0)], Connectivity::Four, |_, c| {
+            *c == '.'
+        });
+        assert_eq!(four.len(), 3);
+        let eight = flood_fill(&map, [Point::new(0, 0)], Connectivity::Eight, |_, c| {
+            *c == '.'
+        });
+        assert_eq!(eight.len(), 6);
+        let blocked = flood_fill(&map, [Point::new(2, 0)], Connectivity::Four, |_, c| {
+            *c == '.'
+        });
+        assert!(blocked.is_empty());
+    }
+
+    #[test]
+    fn test_region_metrics() {
+        let map = Grid::generate_map(GARDEN, |_, c| c).unwrap();
+        let components = connected_components(&map, Connectivity::Four, |_, c| Some(*c));
+        assert_eq!(components.regions.len(), 11);
+        let price: usize = components
+            .regions
+            .iter()
+            .map(|region| region.area() * region.perimeter())
+            .sum();
+        assert_eq!(price, 1930);
+        let discounted: usize = components
+            .regions
+            .iter()
+            .map(|region| region.area() * region.sides())
+            .sum();
+        assert_eq!(discounted, 1206);
+        assert_eq!(components.label(&Point::new(0, 0)), Some(0));
+        assert_eq!(components.label(&Point::new(0, -1)), None);
+        assert_eq!(components.label(&Point::new(10, 0)), None);
+        assert_eq!(components.region_of(&Point::new(9, 9)).unwrap().area(), 13);
+    }
+
+    #[test]
+    fn test_holes_and_exclusions() {
+        let map = Array2D::generate_map("AAA\nABA\nAAA", |_, c| c).unwrap();
+        let components =
+            connected_components(&map, Connectivity::Four, |_, c| (*c == 'A').then_some(()));
+        assert_eq!(components.regions.len(), 1);
+        assert_eq!(components.label(&Point::new(1, 1)), None);
+        let ring = &components.regions[0];
+        assert_eq!((ring.area(), ring.perimeter(), ring.sides()), (8, 16, 8));
+    }
+}
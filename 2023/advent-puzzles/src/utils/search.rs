@@ -0,0 +1,664 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use rustc_hash::FxHashMap;
+
+/// A node in a search graph. `C` is whatever the successors need to look at,
+/// usually the puzzle map.
+pub trait State<C: ?Sized = ()>: Clone + Eq + Hash {
+    /// The states reachable in one move, with the cost of that move.
+    fn successors(&self, context: &C) -> Vec<(Self, usize)>;
+}
+
+#[derive(Debug, Clone)]
+struct Visit<S> {
+    distance: usize,
+    /// The predecessor the distance was first reached from.
+    parent: Option<S>,
+}
+
+/// Everything a search learned: the best known distance to each discovered
+/// state and the predecessor it was reached from. The `*_with_predecessors`
+/// searches also keep every other predecessor on a shortest path.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S> {
+    visits: FxHashMap<S, Visit<S>>,
+    /// Predecessors at the same distance besides each state's parent, only
+    /// kept by the `*_with_predecessors` searches.
+    other_parents: Option<FxHashMap<S, Vec<S>>>,
+    target: Option<S>,
+}
+
+impl<S: Clone + Eq + Hash> SearchResult<S> {
+    fn new(all_predecessors: bool) -> Self {
+        Self {
+            visits: FxHashMap::default(),
+            other_parents: all_predecessors.then(FxHashMap::default),
+            target: None,
+        }
+    }
+
+    /// The first goal state reached, if any.
+    pub fn target(&self) -> Option<&S> {
+        self.target.as_ref()
+    }
+
+    /// Shortest distance to the goal state.
+    pub fn target_distance(&self) -> Option<usize> {
+        self.distance(self.target.as_ref()?)
+    }
+
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.visits.get(state).map(|visit| visit.distance)
+    }
+
+    /// Every discovered state with its shortest distance.
+    pub fn distances(&self) -> impl Iterator<Item = (&S, usize)> {
+        self.visits
+            .iter()
+            .map(|(state, visit)| (state, visit.distance))
+    }
+
+    /// Number of discovered states.
+    pub fn len(&self) -> usize {
+        self.visits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visits.is_empty()
+    }
+
+    /// The states directly before `state` on its shortest paths, or only the
+    /// first one unless the search kept all predecessors. Empty for start
+    /// states and undiscovered states.
+    pub fn predecessors(&self, state: &S) -> impl Iterator<Item = &S> {
+        let others = self
+            .other_parents
+            .as_ref()
+            .and_then(|others| others.get(state));
+        self.visits
+            .get(state)
+            .and_then(|visit| visit.parent.as_ref())
+            .into_iter()
+            .chain(others.into_iter().flatten())
+    }
+
+    /// One shortest path from a start state to `state`, both included.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        let mut visit = self.visits.get(state)?;
+        let mut path = vec![state.clone()];
+        while let Some(parent) = &visit.parent {
+            path.push(parent.clone());
+            visit = &self.visits[parent];
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// One shortest path from a start state to the goal.
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.path_to(self.target.as_ref()?)
+    }
+
+    /// Every state that lies on at least one shortest path to `state`.
+    /// Panics unless the search kept all predecessors.
+    pub fn on_shortest_paths(&self, state: &S) -> HashSet<S> {
+        assert!(
+            self.other_parents.is_some(),
+            "on_shortest_paths needs a *_with_predecessors search"
+        );
+        let mut seen = HashSet::new();
+        if !self.visits.contains_key(state) {
+            return seen;
+        }
+        let mut stack = vec![state.clone()];
+        while let Some(current) = stack.pop() {
+            if seen.insert(current.clone()) {
+                stack.extend(self.predecessors(&current).cloned());
+            }
+        }
+        seen
+    }
+
+    /// Number of distinct shortest paths from the start states to `state`.
+    /// Counts flow forward from the start states, each state passing its
+    /// total on once all of its predecessors have, so long paths need no
+    /// recursion. Zero-cost moves only count along the path the search first
+    /// took, so they never create cycles or infinitely many paths. Panics
+    /// unless the search kept all predecessors.
+    pub fn count_shortest_paths(&self, state: &S) -> usize {
+        let ancestors = self.on_shortest_paths(state);
+        let mut pending = HashMap::new();
+        let mut successors: HashMap<&S, Vec<&S>> = HashMap::new();
+        for current in &ancestors {
+            pending.insert(current, self.predecessors(current).count());
+            for predecessor in self.predecessors(current) {
+                successors.entry(predecessor).or_default().push(current);
+            }
+        }
+
+        let mut ready = pending
+            .iter()
+            .filter(|(_, &waiting)| waiting == 0)
+            .map(|(&start, _)| start)
+            .collect::<Vec<_>>();
+        let mut counts = ready
+            .iter()
+            .map(|&start| (start, 1))
+            .collect::<HashMap<_, _>>();
+        while let Some(current) = ready.pop() {
+            let count = counts[current];
+            for &next in successors.get(current).into_iter().flatten() {
+                *counts.entry(next).or_default() += count;
+                let waiting = pending.get_mut(next).unwrap();
+                *waiting -= 1;
+                if *waiting == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+        counts.get(state).copied().unwrap_or(0)
+    }
+}
+
+/// Queue of states ordered by priority, where the priority never decreases
+/// between pops (the distance, or distance plus heuristic for A*).
+trait Frontier<S> {
+    fn push(&mut self, distance: usize, state: S);
+    fn pop(&mut self) -> Option<(usize, usize, S)>;
+}
+
+struct Fifo<S>(VecDeque<(usize, S)>);
+
+impl<S> Frontier<S> for Fifo<S> {
+    fn push(&mut self, distance: usize, state: S) {
+        self.0.push_back((distance, state));
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize, S)> {
+        self.0
+            .pop_front()
+            .map(|(distance, state)| (distance, distance, state))
+    }
+}
+
+/// Zero-cost moves go to the front, unit-cost moves to the back.
+struct ZeroOne<S> {
+    queue: VecDeque<(usize, S)>,
+    current: usize,
+}
+
+impl<S> Frontier<S> for ZeroOne<S> {
+    fn push(&mut self, distance: usize, state: S) {
+        if distance == self.current {
+            self.queue.push_front((distance, state));
+        } else {
+            self.queue.push_back((distance, state));
+        }
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize, S)> {
+        let (distance, state) = self.queue.pop_front()?;
+        self.current = distance;
+        Some((distance, distance, state))
+    }
+}
+
+/// Dial's algorithm: one bucket per distance, starting at `base`. Only as many
+/// buckets as the largest move cost are ever alive, and emptied buckets are
+/// reused so their allocations are too.
+struct Buckets<S> {
+    buckets: VecDeque<Vec<S>>,
+    spare: Vec<Vec<S>>,
+    base: usize,
+}
+
+impl<S> Frontier<S> for Buckets<S> {
+    fn push(&mut self, distance: usize, state: S) {
+        let offset = distance - self.base;
+        while self.buckets.len() <= offset {
+            self.buckets.push_back(self.spare.pop().unwrap_or_default());
+        }
+        self.buckets[offset].push(state);
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize, S)> {
+        loop {
+            let bucket = self.buckets.front_mut()?;
+            if let Some(state) = bucket.pop() {
+                return Some((self.base, self.base, state));
+            }
+            let empty = self.buckets.pop_front().unwrap();
+            self.spare.push(empty);
+            self.base += 1;
+        }
+    }
+}
+
+struct HeapEntry<S> {
+    priority: usize,
+    distance: usize,
+    state: S,
+}
+
+impl<S> PartialEq for HeapEntry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S> Eq for HeapEntry<S> {}
+
+impl<S> PartialOrd for HeapEntry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reversed so the max-heap pops the lowest priority, preferring the deeper
+/// state on ties.
+impl<S> Ord for HeapEntry<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then(self.distance.cmp(&other.distance))
+    }
+}
+
+struct AStar<S, H> {
+    heap: BinaryHeap<HeapEntry<S>>,
+    heuristic: H,
+}
+
+impl<S, H: FnMut(&S) -> usize> Frontier<S> for AStar<S, H> {
+    fn push(&mut self, distance: usize, state: S) {
+        self.heap.push(HeapEntry {
+            priority: distance + (self.heuristic)(&state),
+            distance,
+            state,
+        });
+    }
+
+    fn pop(&mut self) -> Option<(usize, usize, S)> {
+        self.heap
+            .pop()
+            .map(|entry| (entry.priority, entry.distance, entry.state))
+    }
+}
+
+fn search<S, C, Q, I, G>(
+    mut frontier: Q,
+    starts: I,
+    context: &C,
+    mut goal: G,
+    cost: impl Fn(usize) -> usize,
+    all_predecessors: bool,
+) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    Q: Frontier<S>,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut result = SearchResult::new(all_predecessors);
+    for start in starts {
+        if let Entry::Vacant(entry) = result.visits.entry(start.clone()) {
+            entry.insert(Visit {
+                distance: 0,
+                parent: None,
+            });
+            frontier.push(0, start);
+        }
+    }
+
+    // Once a goal is found, keep going through its priority level so the
+    // goal's predecessor list is complete. Without predecessors to collect,
+    // stop right away.
+    let mut limit = usize::MAX;
+    while let Some((priority, distance, state)) = frontier.pop() {
+        if priority > limit {
+            break;
+        }
+        if result.visits[&state].distance < distance {
+            continue;
+        }
+        if result.target.is_none() && goal(&state) {
+            result.target = Some(state.clone());
+            if result.other_parents.is_none() {
+                break;
+            }
+            limit = priority;
+        }
+
+        for (next, move_cost) in state.successors(context) {
+            let next_distance = distance + cost(move_cost);
+            match result.visits.entry(next) {
+                Entry::Occupied(mut entry) => {
+                    let visit = entry.get_mut();
+                    if next_distance < visit.distance {
+                        *visit = Visit {
+                            distance: next_distance,
+                            parent: Some(state.clone()),
+                        };
+                        if let Some(others) = &mut result.other_parents {
+                            others.remove(entry.key());
+                        }
+                        frontier.push(next_distance, entry.key().clone());
+                    } else if next_distance == visit.distance && next_distance > distance {
+                        // Zero-cost moves between states at the same distance
+                        // are left out, or two states could be each other's
+                        // predecessors
+                        if let Some(others) = &mut result.other_parents {
+                            others
+                                .entry(entry.key().clone())
+                                .or_default()
+                                .push(state.clone());
+                        }
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    frontier.push(next_distance, entry.key().clone());
+                    entry.insert(Visit {
+                        distance: next_distance,
+                        parent: Some(state.clone()),
+                    });
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Breadth-first search where every move costs 1, whatever
+/// [`State::successors`] says. Stops after the first state matching `goal`;
+/// pass `|_| false` to get the distances to everything reachable.
+pub fn bfs<S, C, I, G>(starts: I, context: &C, goal: G) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    search(fifo(), starts, context, goal, |_| 1, false)
+}
+
+/// [`bfs`] that keeps every shortest-path predecessor, for
+/// [`SearchResult::count_shortest_paths`] and
+/// [`SearchResult::on_shortest_paths`].
+pub fn bfs_with_predecessors<S, C, I, G>(starts: I, context: &C, goal: G) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    search(fifo(), starts, context, goal, |_| 1, true)
+}
+
+/// Shortest paths when every move costs 0 or 1. Panics on other costs.
+pub fn zero_one_bfs<S, C, I, G>(starts: I, context: &C, goal: G) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    search(zero_one(), starts, context, goal, zero_one_cost, false)
+}
+
+/// [`zero_one_bfs`] that keeps every shortest-path predecessor.
+pub fn zero_one_bfs_with_predecessors<S, C, I, G>(
+    starts: I,
+    context: &C,
+    goal: G,
+) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    search(zero_one(), starts, context, goal, zero_one_cost, true)
+}
+
+/// Largest move cost [`dijkstra`] accepts. The bucket queue keeps one bucket
+/// per distance up to the largest move, so use [`astar`] with a zero
+/// heuristic for larger costs.
+pub const MAX_DIJKSTRA_COST: usize = 1 << 16;
+
+/// Dijkstra's algorithm on a bucket queue, which beats a heap when move costs
+/// are small integers such as grid digits. Panics on moves costing more than
+/// [`MAX_DIJKSTRA_COST`].
+pub fn dijkstra<S, C, I, G>(starts: I, context: &C, goal: G) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    search(buckets(), starts, context, goal, dijkstra_cost, false)
+}
+
+/// [`dijkstra`] that keeps every shortest-path predecessor.
+pub fn dijkstra_with_predecessors<S, C, I, G>(starts: I, context: &C, goal: G) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    search(buckets(), starts, context, goal, dijkstra_cost, true)
+}
+
+/// A* search. `heuristic` must never overestimate the remaining distance and
+/// should be consistent, or the result may not be the shortest path.
+pub fn astar<S, C, I, G, H>(starts: I, context: &C, goal: G, heuristic: H) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> usize,
+{
+    search(heap(heuristic), starts, context, goal, |cost| cost, false)
+}
+
+/// [`astar`] that keeps every shortest-path predecessor.
+pub fn astar_with_predecessors<S, C, I, G, H>(
+    starts: I,
+    context: &C,
+    goal: G,
+    heuristic: H,
+) -> SearchResult<S>
+where
+    S: State<C>,
+    C: ?Sized,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> usize,
+{
+    search(heap(heuristic), starts, context, goal, |cost| cost, true)
+}
+
+fn fifo<S>() -> Fifo<S> {
+    Fifo(VecDeque::new())
+}
+
+fn zero_one<S>() -> ZeroOne<S> {
+    ZeroOne {
+        queue: VecDeque::new(),
+        current: 0,
+    }
+}
+
+fn zero_one_cost(cost: usize) -> usize {
+    assert!(cost <= 1, "zero_one_bfs got a move costing {}", cost);
+    cost
+}
+
+fn buckets<S>() -> Buckets<S> {
+    Buckets {
+        buckets: VecDeque::new(),
+        spare: Vec::new(),
+        base: 0,
+    }
+}
+
+fn dijkstra_cost(cost: usize) -> usize {
+    assert!(
+        cost <= MAX_DIJKSTRA_COST,
+        "dijkstra got a move costing {}, use astar for costs above {}",
+        cost,
+        MAX_DIJKSTRA_COST
+    );
+    cost
+}
+
+fn heap<S, H>(heuristic: H) -> AStar<S, H> {
+    AStar {
+        heap: BinaryHeap::new(),
+        heuristic,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use array2d::Array2D;
+
+    use super::*;
+    use crate::utils::{map::GenerateMap, map::InteractWithPoint, point::Point};
+
+    impl State<Array2D<char>> for Point {
+        fn successors(&self, map: &Array2D<char>) -> Vec<(Self, usize)> {
+            map.four_neighbours(self)
+                .into_iter()
+                .filter_map(|p| match map.get_point(&p)? {
+                    '#' => None,
+                    c => Some((p, c.to_digit(10).unwrap_or(1) as usize)),
+                })
+                .collect()
+        }
+    }
+
+    const MAZE: &str = "\
+.....
+.#.#.
+.....
+.#.#.
+.....";
+
+    #[test]
+    fn test_bfs_paths() {
+        let map = Array2D::generate_map(MAZE, |_, c| c).unwrap();
+        let end = Point::new(4, 4);
+        let result = bfs_with_predecessors([Point::new(0, 0)], &map, |p| *p == end);
+        assert_eq!(result.target(), Some(&end));
+        assert_eq!(result.target_distance(), Some(8));
+        let path = result.path().unwrap();
+        assert_eq!(path.len(), 9);
+        assert_eq!(path.first(), Some(&Point::new(0, 0)));
+        assert_eq!(result.count_shortest_paths(&end), 6);
+        assert_eq!(result.on_shortest_paths(&end).len(), 21);
+
+        let everything = bfs([Point::new(0, 0)], &map, |_| false);
+        assert_eq!(everything.len(), 21);
+        assert_eq!(everything.distances().map(|(_, d)| d).max(), Some(8));
+        assert_eq!(everything.target(), None);
+    }
+
+    #[test]
+    fn test_count_long_shortest_path() {
+        struct Line;
+        impl State<Line> for u32 {
+            fn successors(&self, _: &Line) -> Vec<(Self, usize)> {
+                vec![(self + 1, 1)]
+            }
+        }
+
+        let end = 100_000;
+        let result = bfs_with_predecessors([0u32], &Line, |&x| x == end);
+        assert_eq!(result.count_shortest_paths(&end), 1);
+        assert_eq!(result.count_shortest_paths(&(end + 2)), 0);
+    }
+
+    #[test]
+    fn test_count_paths_with_zero_cost_moves() {
+        // Two starts joined both ways by free moves, a free self-loop on `a`
+        // and a paid move from each start to `c`
+        struct Free;
+        impl State<Free> for char {
+            fn successors(&self, _: &Free) -> Vec<(Self, usize)> {
+                match self {
+                    'a' => vec![('a', 0), ('b', 0), ('c', 1)],
+                    'b' => vec![('a', 0), ('c', 1)],
+                    _ => Vec::new(),
+                }
+            }
+        }
+
+        for result in [
+            zero_one_bfs_with_predecessors(['a', 'b'], &Free, |_| false),
+            dijkstra_with_predecessors(['a', 'b'], &Free, |_| false),
+        ] {
+            assert_eq!(result.count_shortest_paths(&'a'), 1);
+            assert_eq!(result.count_shortest_paths(&'b'), 1);
+            assert_eq!(result.count_shortest_paths(&'c'), 2);
+        }
+        let from_a = zero_one_bfs_with_predecessors(['a'], &Free, |_| false);
+        assert_eq!(from_a.distance(&'b'), Some(0));
+        assert_eq!(from_a.count_shortest_paths(&'b'), 1);
+        assert_eq!(from_a.count_shortest_paths(&'c'), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "needs a *_with_predecessors search")]
+    fn test_count_paths_needs_predecessors() {
+        let map = Array2D::generate_map(MAZE, |_, c| c).unwrap();
+        bfs([Point::new(0, 0)], &map, |_| false).count_shortest_paths(&Point::new(4, 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "dijkstra got a move costing")]
+    fn test_dijkstra_rejects_huge_costs() {
+        struct Far;
+        impl State<Far> for u8 {
+            fn successors(&self, _: &Far) -> Vec<(Self, usize)> {
+                vec![(self + 1, 1_000_000_000)]
+            }
+        }
+        dijkstra([0u8], &Far, |&n| n == 1);
+    }
+
+    #[test]
+    fn test_weighted_searches_agree() {
+        let map = Array2D::generate_map("19111\n11191\n99991\n99991\n11111", |_, c| c).unwrap();
+        let start = [Point::new(0, 0)];
+        let end = Point::new(0, 4);
+        let expected = Some(14);
+        assert_eq!(
+            dijkstra(start, &map, |p| *p == end).target_distance(),
+            expected
+        );
+        let heuristic = |p: &Point| p.manhattan_distance(&end);
+        assert_eq!(
+            astar(start, &map, |p| *p == end, heuristic).target_distance(),
+            expected
+        );
+        let full = dijkstra(start, &map, |_| false);
+        assert_eq!(full.distance(&end), expected);
+        assert_eq!(full.path_to(&end).unwrap().len(), 15);
+    }
+
+    #[test]
+    fn test_zero_one_bfs() {
+        let map = Array2D::generate_map("0101\n1100\n0011", |_, c| c).unwrap();
+        let result = zero_one_bfs([Point::new(0, 0)], &map, |_| false);
+        assert_eq!(result.distance(&Point::new(3, 1)), Some(1));
+        assert_eq!(result.distance(&Point::new(3, 2)), Some(2));
+    }
+}
@@ -0,0 +1,276 @@
+use std::{collections::HashMap, fmt::Display};
+
+use strum::IntoEnumIterator;
+
+use super::{
+    direction::{Direction, Direction8},
+    grid::Grid,
+    map::{PrintMap, PrintMapWith},
+    point::Point,
+};
+
+/// Unbounded grid that only stores occupied points. The inclusive bounding box
+/// of the occupied points is kept up to date as cells are added and removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The smallest and largest corner of the occupied area, or `None` if the
+    /// grid is empty.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    /// Width and height of the occupied area.
+    pub fn size(&self) -> (usize, usize) {
+        self.bounds.map_or((0, 0), |(min, max)| {
+            ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize)
+        })
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    pub fn get(&self, point: &Point) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    pub fn get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        self.cells.get_mut(point)
+    }
+
+    pub fn insert(&mut self, point: Point, item: T) -> Option<T> {
+        self.grow(&point);
+        self.cells.insert(point, item)
+    }
+
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, point: Point, f: F) -> &mut T {
+        self.grow(&point);
+        self.cells.entry(point).or_insert_with(f)
+    }
+
+    pub fn remove(&mut self, point: &Point) -> Option<T> {
+        let item = self.cells.remove(point)?;
+        let on_edge = self.bounds.is_some_and(|(min, max)| {
+            point.x == min.x || point.x == max.x || point.y == min.y || point.y == max.y
+        });
+        if on_edge {
+            self.recompute_bounds();
+        }
+        Some(item)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    /// Keeps only the cells for which `f` returns `true`.
+    pub fn retain<F: FnMut(&Point, &mut T) -> bool>(&mut self, f: F) {
+        self.cells.retain(f);
+        self.recompute_bounds();
+    }
+
+    fn recompute_bounds(&mut self) {
+        self.bounds = self
+            .cells
+            .keys()
+            .fold(None, |bounds, point| Some(extend_bounds(bounds, point)));
+    }
+
+    fn grow(&mut self, point: &Point) {
+        self.bounds = Some(extend_bounds(self.bounds, point));
+    }
+
+    /// Iterates the occupied cells in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Point, &mut T)> {
+        self.cells.iter_mut()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &Point> {
+        self.cells.keys()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    /// The occupied orthogonal neighbours of `point`.
+    pub fn four_neighbours(&self, point: &Point) -> impl Iterator<Item = (Point, &T)> {
+        let point = *point;
+        Direction::iter().filter_map(move |direction| {
+            let neighbour = point + direction;
+            Some((neighbour, self.cells.get(&neighbour)?))
+        })
+    }
+
+    /// The occupied neighbours of `point`, including diagonals.
+    pub fn eight_neighbours(&self, point: &Point) -> impl Iterator<Item = (Point, &T)> {
+        let point = *point;
+        Direction8::iter().filter_map(move |direction| {
+            let neighbour = point + direction;
+            Some((neighbour, self.cells.get(&neighbour)?))
+        })
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Copies the occupied bounding box into a dense grid, filling gaps with
+    /// `empty`. Also returns the offset to add to a dense point to get back
+    /// the sparse point, which is the top-left corner of the bounds.
+    pub fn to_dense(&self, empty: T) -> (Grid<T>, Point) {
+        let Some((min, _)) = self.bounds else {
+            return (Grid::filled_with(empty, 0, 0), Point::default());
+        };
+        let (width, height) = self.size();
+        let grid = Grid::from_fn(width, height, |point| {
+            self.cells
+                .get(&(point + min))
+                .cloned()
+                .unwrap_or_else(|| empty.clone())
+        });
+        (grid, min)
+    }
+
+    /// Collects the cells of a dense grid for which `keep` returns `true`,
+    /// shifted by `offset`.
+    pub fn from_dense<F: FnMut(&T) -> bool>(grid: &Grid<T>, offset: Point, mut keep: F) -> Self {
+        grid.iter()
+            .filter(|(_, item)| keep(item))
+            .map(|(point, item)| (point + offset, item.clone()))
+            .collect()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(point, item)| {
+            self.insert(point, item);
+        });
+    }
+}
+
+/// Prints the occupied bounding box, with blanks for empty cells.
+impl<T: Clone + Display> PrintMap for SparseGrid<T> {
+    fn print_map(&self) {
+        self.print_with(|_, item, string| match item {
+            Some(item) => string.push_str(&item.to_string()),
+            None => string.push(' '),
+        });
+    }
+}
+
+/// Renders the occupied bounding box row by row. `f` receives the sparse
+/// coordinates and `None` for empty cells.
+impl<T: Clone> PrintMapWith for SparseGrid<T> {
+    type Item = Option<T>;
+
+    fn print_with<F: Fn(&Point, &Self::Item, &mut String)>(&self, f: F) {
+        let Some((min, max)) = self.bounds else {
+            return;
+        };
+        let mut string = String::new();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let point = Point::new(x, y);
+                f(&point, &self.cells.get(&point).cloned(), &mut string);
+            }
+            string.push('\n');
+        }
+        print!("{}", string);
+    }
+}
+
+/// The smallest bounds covering both `bounds` and `point`.
+fn extend_bounds(bounds: Option<(Point, Point)>, point: &Point) -> (Point, Point) {
+    match bounds {
+        None => (*point, *point),
+        Some((min, max)) => (
+            Point::new(min.x.min(point.x), min.y.min(point.y)),
+            Point::new(max.x.max(point.x), max.y.max(point.y)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_follow_changes() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        grid.insert(Point::new(-2, 3), 'a');
+        grid.insert(Point::new(4, -1), 'b');
+        grid.insert(Point::new(0, 0), 'c');
+        assert_eq!(grid.bounds(), Some((Point::new(-2, -1), Point::new(4, 3))));
+        assert_eq!(grid.size(), (7, 5));
+
+        grid.remove(&Point::new(4, -1));
+        assert_eq!(grid.bounds(), Some((Point::new(-2, 0), Point::new(0, 3))));
+        grid.retain(|_, c| *c == 'c');
+        assert_eq!(grid.bounds(), Some((Point::new(0, 0), Point::new(0, 0))));
+        grid.clear();
+        assert_eq!(grid.size(), (0, 0));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = [(0, 0), (1, 0), (1, 1), (-5, -5)]
+            .into_iter()
+            .map(|(x, y)| (Point::new(x, y), ()))
+            .collect::<SparseGrid<_>>();
+        assert_eq!(grid.four_neighbours(&Point::new(0, 0)).count(), 1);
+        assert_eq!(grid.eight_neighbours(&Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.eight_neighbours(&Point::new(0, 1)).count(), 3);
+    }
+
+    #[test]
+    fn test_dense_round_trip() {
+        let grid = [(-1, -1), (1, 0)]
+            .into_iter()
+            .map(|(x, y)| (Point::new(x, y), '#'))
+            .collect::<SparseGrid<_>>();
+        let (dense, offset) = grid.to_dense('.');
+        assert_eq!(offset, Point::new(-1, -1));
+        assert_eq!(dense.to_string(), "#..\n..#\n");
+        assert_eq!(SparseGrid::from_dense(&dense, offset, |c| *c == '#'), grid);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use super::{map::GenerateMap, point::Point};
+
+pub use advent_derive::GridTile;
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum ParseTileError {
+    #[error("invalid tile {0:?}")]
+    InvalidChar(char),
+    #[error("invalid tile {character:?} at {point}")]
+    InvalidTile { character: char, point: Point },
+    #[error(transparent)]
+    Shape(#[from] array2d::Error),
+}
+
+/// A grid cell that maps to and from single characters. Usually derived with
+/// `#[derive(GridTile)]` and one `#[tile(...)]` attribute per unit variant:
+///
+/// ```ignore
+/// #[derive(GridTile, Clone, Copy)]
+/// enum Tile {
+///     #[tile('.', marker = 'S')] // 'S' is a plot whose position is recorded
+///     GardenPlot,
+///     #[tile('#')]
+///     Rock,
+///     #[tile('|', display = '│')] // parsed from '|', printed as '│'
+///     Pipe,
+/// }
+/// ```
+///
+/// Every listed character parses to the variant and the first one is used for
+/// `Display` unless `display` overrides it.
+pub trait GridTile: Sized {
+    /// Characters that parse to a tile and also have their positions recorded
+    /// by [`GridTile::parse_map`].
+    const MARKERS: &'static [char];
+
+    fn from_char(c: char) -> Option<Self>;
+
+    fn to_char(&self) -> char;
+
+    /// Parses a whole map, reporting the coordinates of the first unknown
+    /// character.
+    fn parse_map<G>(input: &str) -> Result<TileMap<G>, ParseTileError>
+    where
+        G: GenerateMap<Item = Self>,
+    {
+        let mut markers: HashMap<char, Vec<Point>> = HashMap::new();
+        for (y, line) in input.lines().enumerate() {
+            for (x, character) in line.chars().enumerate() {
+                let point = Point::new(x as i32, y as i32);
+                if Self::from_char(character).is_none() {
+                    return Err(ParseTileError::InvalidTile { character, point });
+                }
+                if Self::MARKERS.contains(&character) {
+                    markers.entry(character).or_default().push(point);
+                }
+            }
+        }
+
+        let map = G::generate_map(input, |_, c| {
+            Self::from_char(c).expect("Characters should have been validated")
+        })?;
+        Ok(TileMap { map, markers })
+    }
+}
+
+/// A parsed map together with the positions of its marker characters, in
+/// row-major order.
+#[derive(Debug, Clone)]
+pub struct TileMap<G> {
+    pub map: G,
+    pub markers: HashMap<char, Vec<Point>>,
+}
+
+impl<G> TileMap<G> {
+    /// The first position of `marker`, if it appears in the input.
+    pub fn marker(&self, marker: char) -> Option<Point> {
+        self.markers.get(&marker)?.first().copied()
+    }
+
+    pub fn marker_positions(&self, marker: char) -> &[Point] {
+        self.markers.get(&marker).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use array2d::Array2D;
+
+    use super::*;
+    use crate::utils::grid::Grid;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, GridTile)]
+    enum Tile {
+        #[tile('.', marker = 'S', marker = 'E')]
+        Open,
+        #[tile('#', 'X')]
+        Wall,
+        #[tile('|', display = '│')]
+        Pipe,
+    }
+
+    #[test]
+    fn test_chars() {
+        assert_eq!(Tile::try_from('X'), Ok(Tile::Wall));
+        assert_eq!(Tile::try_from('S'), Ok(Tile::Open));
+        assert_eq!(Tile::try_from('?'), Err(ParseTileError::InvalidChar('?')));
+        assert_eq!(Tile::Wall.to_string(), "#");
+        assert_eq!(Tile::Pipe.to_string(), "│");
+    }
+
+    #[test]
+    fn test_parse_map() {
+        let parsed = Tile::parse_map::<Array2D<_>>("S.#\n|.E\n..S").unwrap();
+        assert_eq!(parsed.map.get(1, 0), Some(&Tile::Pipe));
+        assert_eq!(parsed.marker('E'), Some(Point::new(2, 1)));
+        assert_eq!(parsed.marker_positions('S').len(), 2);
+        assert_eq!(parsed.marker('?'), None);
+
+        let grid = Tile::parse_map::<Grid<_>>("..\n#.").unwrap().map;
+        assert_eq!(grid[Point::new(0, 1)], Tile::Wall);
+
+        assert_eq!(
+            Tile::parse_map::<Array2D<_>>("..\n.?").unwrap_err(),
+            ParseTileError::InvalidTile {
+                character: '?',
+                point: Point::new(1, 1)
+            }
+        );
+    }
+}
@@ -1,12 +1,17 @@
 use advent_derive::*;
+use advent_puzzles::utils::memoize::{
+    clear_persisted_caches, format_duration, memoize_stats_json, set_persist_dir,
+    DEFAULT_PERSIST_CACHE_DIR,
+};
 use advent_puzzles::{clear_all_caches_and_stats, print_memoize_stats};
 use chrono::Datelike;
 use clap::Parser;
 use colored::*;
 use serde::Deserialize;
 use std::fs;
+use std::io::Write;
 use std::panic;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -22,6 +27,9 @@ enum Cli {
         part: Part,
         #[clap(short, long)]
         example: bool,
+        /// Append memoization statistics of each part as JSON lines to this file
+        #[clap(long)]
+        stats_json: Option<PathBuf>,
     },
     Bench {
         #[clap(value_enum)]
@@ -41,6 +49,21 @@ enum Cli {
         #[clap(short, long)]
         day: u8,
     },
+    Cache {
+        #[clap(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum CacheAction {
+    /// Remove persisted `#[memoize(persist)]` caches
+    Clear {
+        #[clap(value_enum)]
+        year: Option<Years>,
+        #[clap(value_enum, short, long)]
+        day: Option<Days>,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, clap::ValueEnum)]
@@ -82,14 +105,16 @@ fn main() {
             day,
             part,
             example,
-        } => handle_run(year, day, part, example),
+            stats_json,
+        } => handle_run(year, day, part, example, stats_json.as_deref()),
         Cli::Bench { year, day } => handle_bench(year, day),
         Cli::Test { year, day } => handle_test(year, day),
         Cli::New { year, day } => handle_new(year, day),
+        Cli::Cache { action } => handle_cache(action),
     }
 }
 
-fn handle_run(year: Years, day: Days, part: Part, example: bool) {
+fn handle_run(year: Years, day: Days, part: Part, example: bool, stats_json: Option<&Path>) {
     let year_num: u16 = year.into();
     let day_num: u8 = day.into();
 
@@ -108,27 +133,29 @@ fn handle_run(year: Years, day: Days, part: Part, example: bool) {
     match part {
         Part::Part1 => {
             let input = find_input(year_num, day_num, example, false);
-            run_part(year_num, day_num, false, &input, "Part 1");
+            run_part(year_num, day_num, false, &input, "Part 1", stats_json);
         }
         Part::Part2 => {
             let input = find_input(year_num, day_num, example, true);
-            run_part(year_num, day_num, true, &input, "Part 2");
+            run_part(year_num, day_num, true, &input, "Part 2", stats_json);
         }
         Part::Both => {
             let input1 = find_input(year_num, day_num, example, false);
-            run_part(year_num, day_num, false, &input1, "Part 1");
+            run_part(year_num, day_num, false, &input1, "Part 1", stats_json);
 
             let input2 = find_input(year_num, day_num, example, true);
-            run_part(year_num, day_num, true, &input2, "Part 2");
+            run_part(year_num, day_num, true, &input2, "Part 2", stats_json);
         }
     }
 }
 
-fn run_part(year: u16, day: u8, part: bool, input: &str, label: &str) {
+fn run_part(year: u16, day: u8, part: bool, input: &str, label: &str, stats_json: Option<&Path>) {
     clear_all_caches_and_stats();
+    set_persist_dir(Some(PathBuf::from(DEFAULT_PERSIST_CACHE_DIR)));
     let start = Instant::now();
     let result = advent_puzzles::run_day(year, day, part, input.to_string());
     let elapsed = start.elapsed();
+    set_persist_dir(None);
 
     let time_str = format_duration(elapsed);
     println!(
@@ -138,18 +165,45 @@ fn run_part(year: u16, day: u8, part: bool, input: &str, label: &str) {
         format!("({})", time_str).dimmed()
     );
     print_memoize_stats();
+
+    if let Some(path) = stats_json {
+        let line = memoize_stats_json(year, day, if part { 2 } else { 1 });
+        let result = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(err) = result {
+            eprintln!(
+                "{}",
+                format!("Failed to write stats to {}: {}", path.display(), err).red()
+            );
+        }
+    }
 }
 
-fn format_duration(duration: std::time::Duration) -> String {
-    let nanos = duration.as_nanos();
-    if nanos < 1_000 {
-        format!("{}ns", nanos)
-    } else if nanos < 1_000_000 {
-        format!("{:.2}µs", nanos as f64 / 1_000.0)
-    } else if nanos < 1_000_000_000 {
-        format!("{:.2}ms", nanos as f64 / 1_000_000.0)
-    } else {
-        format!("{:.2}s", nanos as f64 / 1_000_000_000.0)
+fn handle_cache(action: CacheAction) {
+    match action {
+        CacheAction::Clear { year, day } => {
+            let year: Option<u16> = year.map(Into::into);
+            let day: Option<u8> = day.map(Into::into);
+            if year.is_none() && day.is_some() {
+                eprintln!("{}", "A year is required when clearing a single day".red());
+                std::process::exit(1);
+            }
+
+            match clear_persisted_caches(Path::new(DEFAULT_PERSIST_CACHE_DIR), year, day) {
+                Ok(removed) => println!(
+                    "{} {}",
+                    "✓".green().bold(),
+                    format!("Removed {} cache file(s)", removed).green()
+                ),
+                Err(err) => {
+                    eprintln!("{}", format!("Failed to clear caches: {}", err).red());
+                    std::process::exit(1);
+                }
+            }
+        }
     }
 }
 